@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+use crate::challenge::{Answer, Challenge};
+
+// Known-good answers, stored in a TOML file such as:
+//
+// 01a = "24000"
+// 01b = "45000"
+// 02a = ""
+//
+// Pictures are stored as multi-line strings.
+pub struct Answers {
+    path: PathBuf,
+    answers: BTreeMap<String, String>,
+}
+
+pub enum Verification {
+    Pass,
+    Fail(String),
+    Unknown,
+}
+
+impl Answers {
+    pub fn load(path: PathBuf) -> anyhow::Result<Self> {
+        let answers = match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content)
+                .with_context(|| format!("{} is not a valid answers file", path.display()))?,
+            Err(error) if error.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => {
+                return Err(error).with_context(|| format!("Could not read {}", path.display()))
+            }
+        };
+
+        Ok(Answers { path, answers })
+    }
+
+    // Empty entries stand for challenges which have not been solved yet
+    pub fn get(&self, challenge: &Challenge) -> Option<&str> {
+        self.answers
+            .get(&challenge.id())
+            .map(String::as_str)
+            .filter(|answer| !answer.is_empty())
+    }
+
+    pub fn insert(&mut self, challenge: &Challenge, answer: String) {
+        self.answers.insert(challenge.id(), answer);
+    }
+
+    // Adds an empty entry for the challenge, unless it already has one
+    pub fn reserve(&mut self, challenge: &Challenge) {
+        self.answers.entry(challenge.id()).or_default();
+    }
+
+    pub fn verify(&self, challenge: &Challenge, answer: &Answer) -> Verification {
+        match self.get(challenge) {
+            Some(expected) if answer.matches(expected) => Verification::Pass,
+            Some(expected) => Verification::Fail(expected.to_string()),
+            None => Verification::Unknown,
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let content = toml::to_string(&self.answers)?;
+        std::fs::write(&self.path, content)
+            .with_context(|| format!("Could not write {}", self.path.display()))
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context};
+
+use crate::challenge::{Challenge, Parameters, Solutions};
+
+// Summary of the durations measured over all iterations of a challenge
+#[derive(Copy, Clone, Debug)]
+pub struct Statistics {
+    pub iterations: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Statistics {
+    pub fn new(mut durations: Vec<Duration>) -> anyhow::Result<Self> {
+        if durations.is_empty() {
+            bail!("Statistics require at least one measurement");
+        }
+
+        durations.sort();
+
+        let count = durations.len();
+        let middle = count / 2;
+
+        let median = if count.is_multiple_of(2) {
+            (durations[middle - 1] + durations[middle]) / 2
+        } else {
+            durations[middle]
+        };
+
+        // Nearest rank, so that a small number of iterations still picks an actual measurement
+        let p95 = durations[(count * 95).div_ceil(100) - 1];
+
+        Ok(Statistics {
+            iterations: count,
+            min: durations[0],
+            median,
+            mean: durations.iter().sum::<Duration>() / count as u32,
+            p95,
+        })
+    }
+}
+
+// Durations of the runs of a challenge. Parsing is measured on its own for puzzles which parse
+// before solving, while days made of plain functions parse as part of solving.
+#[derive(Copy, Clone, Debug)]
+pub struct Measurement {
+    pub parse: Option<Statistics>,
+    pub solve: Statistics,
+    pub total: Statistics,
+}
+
+// Runs the challenge warmup times without recording anything, then iterations times measuring
+// each run on its own. Every run parses the input again, as a real one would.
+pub fn measure(
+    solutions: &Solutions,
+    challenge: &Challenge,
+    input: &str,
+    parameters: &Parameters,
+    warmup: usize,
+    iterations: usize,
+) -> anyhow::Result<Measurement> {
+    let puzzle = solutions.get(challenge)?;
+
+    for _ in 0..warmup {
+        solutions.run(challenge, input, parameters)?;
+    }
+
+    let mut parse = Vec::with_capacity(iterations);
+    let mut solve = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let model = puzzle.parse(input, parameters)?;
+        parse.push(start.elapsed());
+
+        let start = Instant::now();
+        puzzle.solve(challenge.part(), &model, parameters)?;
+        solve.push(start.elapsed());
+    }
+
+    let total = parse.iter().zip(&solve).map(|(a, b)| *a + *b).collect();
+
+    Ok(Measurement {
+        parse: puzzle
+            .parses()
+            .then(|| Statistics::new(parse))
+            .transpose()?,
+        solve: Statistics::new(solve)?,
+        total: Statistics::new(total)?,
+    })
+}
+
+// Median durations of a previous benchmark in nanoseconds, stored in a TOML file such as:
+//
+// 01a = 15200
+// 01b = 16900
+pub struct Baseline {
+    path: PathBuf,
+    medians: BTreeMap<String, u64>,
+}
+
+pub enum Comparison {
+    // Relative change of the median, 0.1 meaning 10% slower
+    Change(f64),
+    Regression(f64),
+    Unknown,
+}
+
+impl Baseline {
+    pub fn new(path: PathBuf) -> Self {
+        Baseline {
+            path,
+            medians: BTreeMap::new(),
+        }
+    }
+
+    pub fn load(path: PathBuf) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+
+        let medians = toml::from_str(&content)
+            .with_context(|| format!("{} is not a valid baseline file", path.display()))?;
+
+        Ok(Baseline { path, medians })
+    }
+
+    pub fn insert(&mut self, challenge: &Challenge, statistics: &Statistics) {
+        let median = statistics.median.as_nanos().try_into().unwrap_or(u64::MAX);
+        self.medians.insert(challenge.id(), median);
+    }
+
+    // Compares the medians, anything slower than threshold (0.1 meaning 10%) is a regression
+    pub fn compare(
+        &self,
+        challenge: &Challenge,
+        statistics: &Statistics,
+        threshold: f64,
+    ) -> Comparison {
+        let baseline = match self.medians.get(&challenge.id()) {
+            Some(&median) if median > 0 => median as f64,
+            _ => return Comparison::Unknown,
+        };
+
+        let change = statistics.median.as_nanos() as f64 / baseline - 1.0;
+
+        if change > threshold {
+            Comparison::Regression(change)
+        } else {
+            Comparison::Change(change)
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let content = toml::to_string(&self.medians)?;
+        std::fs::write(&self.path, content)
+            .with_context(|| format!("Could not write {}", self.path.display()))
+    }
+}
+
+impl Display for Statistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min = {:?}, median = {:?}, mean = {:?}, p95 = {:?}",
+            self.min, self.median, self.mean, self.p95
+        )
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Comparison::Change(change) => write!(f, "{:+.1}%", change * 100.0),
+            Comparison::Regression(change) => write!(f, "{:+.1}% (regression)", change * 100.0),
+            Comparison::Unknown => write!(f, "no baseline"),
+        }
+    }
+}
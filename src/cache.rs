@@ -0,0 +1,133 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Context;
+
+use crate::challenge::{Day, Year};
+
+// Inputs are stored per year, such as <directory>/2022/day01.txt
+pub struct Cache {
+    directory: PathBuf,
+}
+
+impl Cache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Cache {
+            directory: directory.into(),
+        }
+    }
+
+    // Follows the XDG base directory spec, resulting in ~/.cache/aoc by default
+    pub fn from_env() -> anyhow::Result<Self> {
+        let root = match std::env::var_os("XDG_CACHE_HOME") {
+            Some(directory) if !directory.is_empty() => PathBuf::from(directory),
+            _ => std::env::var_os("HOME")
+                .map(|home| Path::new(&home).join(".cache"))
+                .context(
+                    "Cannot locate the cache directory, neither XDG_CACHE_HOME nor HOME is defined",
+                )?,
+        };
+
+        Ok(Cache::new(root.join("aoc")))
+    }
+
+    pub fn path(&self, year: Year, day: Day) -> PathBuf {
+        self.directory
+            .join(year.to_string())
+            .join(format!("day{:02}.txt", u8::from(day)))
+    }
+
+    // Puzzle pages are stored next to the inputs, such as <directory>/2022/day01.html
+    pub fn page_path(&self, year: Year, day: Day) -> PathBuf {
+        self.path(year, day).with_extension("html")
+    }
+
+    pub fn read(&self, year: Year, day: Day) -> anyhow::Result<Option<String>> {
+        read_file(&self.path(year, day))
+    }
+
+    pub fn write(&self, year: Year, day: Day, input: &str) -> anyhow::Result<()> {
+        self.write_file(&self.path(year, day), input)
+    }
+
+    pub fn read_page(&self, year: Year, day: Day) -> anyhow::Result<Option<String>> {
+        read_file(&self.page_path(year, day))
+    }
+
+    pub fn write_page(&self, year: Year, day: Day, page: &str) -> anyhow::Result<()> {
+        self.write_file(&self.page_path(year, day), page)
+    }
+
+    // Leaderboards are stored per year as well, such as <directory>/2022/leaderboard-123.json
+    pub fn leaderboard_path(&self, year: Year, id: u64) -> PathBuf {
+        self.directory
+            .join(year.to_string())
+            .join(format!("leaderboard-{}.json", id))
+    }
+
+    // Only returns leaderboards which were stored less than max_age ago
+    pub fn read_leaderboard(
+        &self,
+        year: Year,
+        id: u64,
+        max_age: Duration,
+    ) -> anyhow::Result<Option<String>> {
+        let path = self.leaderboard_path(year, id);
+
+        let modified = match std::fs::metadata(&path) {
+            Ok(metadata) => metadata
+                .modified()
+                .with_context(|| format!("Could not access {}", path.display()))?,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => {
+                return Err(error).with_context(|| format!("Could not access {}", path.display()))
+            }
+        };
+
+        // A modification time in the future counts as fresh
+        match modified.elapsed() {
+            Ok(age) if age >= max_age => Ok(None),
+            _ => read_file(&path),
+        }
+    }
+
+    pub fn write_leaderboard(&self, year: Year, id: u64, json: &str) -> anyhow::Result<()> {
+        self.write_file(&self.leaderboard_path(year, id), json)
+    }
+
+    pub fn invalidate(&self, year: Year, day: Day) -> anyhow::Result<()> {
+        let path = self.path(year, day);
+
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(()),
+            Err(error) => {
+                Err(error).with_context(|| format!("Could not remove {}", path.display()))
+            }
+        }
+    }
+
+    fn write_file(&self, path: &Path, content: &str) -> anyhow::Result<()> {
+        let directory = path.parent().unwrap_or(&self.directory);
+
+        std::fs::create_dir_all(directory)
+            .with_context(|| format!("Could not create {}", directory.display()))?;
+
+        // Write to a temporary file first, so an interrupted download never leaves a partial file
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        std::fs::write(&temporary, content)
+            .with_context(|| format!("Could not write {}", Path::new(&temporary).display()))?;
+        std::fs::rename(&temporary, path)
+            .with_context(|| format!("Could not write {}", path.display()))
+    }
+}
+
+fn read_file(path: &Path) -> anyhow::Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error).with_context(|| format!("Could not read {}", path.display())),
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::Display;
+
+use anyhow::bail;
+use serde::{Serialize, Serializer};
+
+// Value returned by a solution. Most puzzles ask for a number, some for a word, while a few draw
+// a picture whose rows make up a grid. Pictures of letters are read, keeping the picture around.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    Grid(Vec<String>),
+    Letters { text: String, picture: Vec<String> },
+}
+
+impl Answer {
+    pub fn grid(rows: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Answer::Grid(rows.into_iter().map(Into::into).collect())
+    }
+
+    // Reads the letters drawn with # and . in the rows, see ocr::read
+    pub fn letters(rows: impl IntoIterator<Item = impl Into<String>>) -> anyhow::Result<Self> {
+        let picture = rows.into_iter().map(Into::into).collect::<Vec<String>>();
+        let text = crate::ocr::read(&picture)?;
+        Ok(Answer::Letters { text, picture })
+    }
+
+    pub fn is_grid(&self) -> bool {
+        matches!(self, Answer::Grid(_))
+    }
+
+    pub fn picture(&self) -> Option<&[String]> {
+        match self {
+            Answer::Grid(rows) | Answer::Letters { picture: rows, .. } => Some(rows),
+            _ => None,
+        }
+    }
+
+    // Compares the answer with one stored as text, ignoring trailing whitespace of grid rows.
+    // Letters also match their picture, which is what used to be stored for them.
+    pub fn matches(&self, expected: &str) -> bool {
+        let same_rows = |rows: &[String]| {
+            let expected = expected.trim_end().lines().map(str::trim_end);
+            rows.iter().map(|row| row.trim_end()).eq(expected)
+        };
+
+        match self {
+            Answer::Integer(value) => expected.trim().parse::<i128>() == Ok(*value),
+            Answer::Text(text) => text == expected,
+            Answer::Grid(rows) => same_rows(rows),
+            Answer::Letters { text, picture } => text == expected || same_rows(picture),
+        }
+    }
+
+    // Value sent to the site, which only accepts a single line
+    pub fn submission(&self) -> anyhow::Result<String> {
+        match self {
+            Answer::Integer(value) => Ok(value.to_string()),
+            Answer::Text(text) | Answer::Letters { text, .. } => Ok(text.clone()),
+            Answer::Grid(_) => bail!(
+                "Pictures cannot be submitted as they are, read the letters and pass them with --answer"
+            ),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Text(text) | Answer::Letters { text, .. } => write!(f, "{}", text),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+// Numbers and text are written as they are, pictures as arrays of rows
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Integer(value) => serializer.serialize_i128(*value),
+            Answer::Text(text) | Answer::Letters { text, .. } => serializer.serialize_str(text),
+            Answer::Grid(rows) => rows.serialize(serializer),
+        }
+    }
+}
+
+macro_rules! from_integer {
+    ($($type:ty),*) => {
+        $(
+            impl From<$type> for Answer {
+                fn from(value: $type) -> Self {
+                    Answer::Integer(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
@@ -0,0 +1,61 @@
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+thread_local! {
+    // Token of the solution running on this thread
+    static CURRENT: RefCell<Cancellation> = RefCell::new(Cancellation::new());
+}
+
+// Asks a running solution to stop, such as once it has run out of time. Solutions are not
+// interrupted, long searches check the token of their thread every now and then instead.
+#[derive(Clone, Default, Debug)]
+pub struct Cancellation(Arc<AtomicBool>);
+
+// Returned by solutions which stopped because they were cancelled
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The solution was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+impl Cancellation {
+    pub fn new() -> Self {
+        Cancellation::default()
+    }
+
+    // Token of the solution running on this thread, which is never cancelled outside of one
+    pub fn current() -> Self {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.is_cancelled() {
+            Err(Cancelled.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    // Makes this the token of the current thread while running the solution
+    pub fn scope<T>(&self, solution: impl FnOnce() -> T) -> T {
+        let previous = CURRENT.with(|current| current.replace(self.clone()));
+        let result = solution();
+        CURRENT.with(|current| current.replace(previous));
+        result
+    }
+}
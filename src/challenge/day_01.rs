@@ -1,26 +1,44 @@
+use super::{register, Answer, Day, LineParser, Parameters};
 use anyhow::Context;
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    input
-        .split(|line| line.is_empty())
-        .map(total_calories)
-        .max()
-        .context("Input is empty")
+register! {
+    day: Day01,
+    title: "Calorie Counting",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let mut calories = input
-        .split(|line| line.is_empty())
-        .map(total_calories)
-        .collect::<Vec<_>>();
+// Total calories carried by every elf, whose inventories are separated by empty lines
+pub struct Elves(Vec<usize>);
 
-    calories.sort();
-    Ok(calories.iter().rev().take(3).sum::<usize>())
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Elves> {
+    let mut elves = Vec::new();
+    let mut total = None;
+
+    for (index, line) in input.iter().enumerate() {
+        if line.is_empty() {
+            elves.extend(total.take());
+            continue;
+        }
+
+        let mut parser = LineParser::new(Day::Day01, index, line);
+        let calories = parser.number::<usize>()?;
+        parser.end()?;
+
+        *total.get_or_insert(0) += calories;
+    }
+
+    elves.extend(total);
+    Ok(Elves(elves))
+}
+
+pub fn part_a(elves: &Elves) -> anyhow::Result<impl Into<Answer>> {
+    elves.0.iter().copied().max().context("Input is empty")
 }
 
-fn total_calories(inventory: &[&str]) -> usize {
-    inventory
-        .iter()
-        .filter_map(|calories| calories.parse::<usize>().ok())
-        .sum::<usize>()
+pub fn part_b(elves: &Elves) -> anyhow::Result<impl Into<Answer>> {
+    let mut calories = elves.0.clone();
+    calories.sort();
+    Ok(calories.iter().rev().take(3).sum::<usize>())
 }
@@ -1,12 +1,40 @@
+use super::{register, Answer, Day, LineParser, Parameters};
 use num_enum::{FromPrimitive, IntoPrimitive};
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let result = input
+register! {
+    day: Day02,
+    title: "Rock Paper Scissors",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
+// Hand of the opponent and the second column of every round, which is 0, 1 or 2 for X, Y or Z
+pub struct Rounds(Vec<(Hand, usize)>);
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Rounds> {
+    let rounds = input
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let mut parser = LineParser::new(Day::Day02, index, line);
+            let opponent = parser.one_of(&['A', 'B', 'C'])? as usize - 'A' as usize;
+            parser.expect(" ")?;
+            let column = parser.one_of(&['X', 'Y', 'Z'])? as usize - 'X' as usize;
+            parser.end()?;
+            Ok((Hand::from(opponent), column))
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(Rounds(rounds))
+}
+
+pub fn part_a(rounds: &Rounds) -> anyhow::Result<impl Into<Answer>> {
+    let result = rounds
+        .0
         .iter()
-        .map(|game| {
-            let bytes = game.as_bytes();
-            let opponent = Hand::from(bytes[0]);
-            let player = Hand::from(bytes[2]);
+        .map(|&(opponent, column)| {
+            let player = Hand::from(column);
             compute_outcome(opponent, player).score() + player.score()
         })
         .sum::<usize>();
@@ -14,13 +42,13 @@ pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
     Ok(result)
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let result = input
+pub fn part_b(rounds: &Rounds) -> anyhow::Result<impl Into<Answer>> {
+    let result = rounds
+        .0
         .iter()
-        .map(|game| {
-            let bytes = game.as_bytes();
-            let opponent = Hand::from(bytes[0]);
-            let outcome = Outcome::from(bytes[2]);
+        .map(|&(opponent, column)| {
+            // X, Y and Z stand for losing, drawing and winning
+            let outcome = Outcome::from((column + 2) % 3);
             outcome.score() + compute_hand(opponent, outcome).score()
         })
         .sum::<usize>();
@@ -45,20 +73,6 @@ impl Hand {
     }
 }
 
-impl From<u8> for Hand {
-    fn from(value: u8) -> Self {
-        match value {
-            b'A' => Hand::Rock,
-            b'B' => Hand::Paper,
-            b'C' => Hand::Scissors,
-            b'X' => Hand::Rock,
-            b'Y' => Hand::Paper,
-            b'Z' => Hand::Scissors,
-            _ => unreachable!(),
-        }
-    }
-}
-
 #[derive(
     FromPrimitive, IntoPrimitive, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug,
 )]
@@ -80,17 +94,6 @@ impl Outcome {
     }
 }
 
-impl From<u8> for Outcome {
-    fn from(value: u8) -> Self {
-        match value {
-            b'X' => Outcome::Lose,
-            b'Y' => Outcome::Draw,
-            b'Z' => Outcome::Win,
-            _ => unreachable!(),
-        }
-    }
-}
-
 fn compute_outcome(opponent: Hand, player: Hand) -> Outcome {
     let opponent_index = usize::from(opponent);
     let player_index = usize::from(player);
@@ -1,22 +1,65 @@
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let result = input
-        .iter()
-        .map(|line| {
-            let items = line.as_bytes();
-            let length = items.len() / 2;
-            [
-                Rucksack::new(&items[..length]),
-                Rucksack::new(&items[length..]),
-            ]
-            .score()
-        })
-        .sum::<usize>();
+use super::{register, Answer, Day, LineParser, Parameters};
+use anyhow::bail;
+
+register! {
+    day: Day03,
+    title: "Rucksack Reorganization",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
+// Items of every rucksack, which are all letters
+pub struct Rucksacks(Vec<String>);
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Rucksacks> {
+    let mut rucksacks = Vec::with_capacity(input.len());
+
+    for (index, line) in input.iter().enumerate() {
+        if let Some(position) = line.find(|char: char| !char.is_ascii_alphabetic()) {
+            let parser = LineParser::new(Day::Day03, index, line);
+            return Err(parser.error_at(position, "an item").into());
+        }
+
+        rucksacks.push(line.to_string());
+    }
+
+    Ok(Rucksacks(rucksacks))
+}
+
+pub fn part_a(rucksacks: &Rucksacks) -> anyhow::Result<impl Into<Answer>> {
+    let mut result = 0;
+
+    for (index, line) in rucksacks.0.iter().enumerate() {
+        // Both compartments hold the same number of items
+        if line.len() % 2 != 0 {
+            let parser = LineParser::new(Day::Day03, index, line);
+            return Err(parser.error_at(0, "an even number of items").into());
+        }
+
+        let items = line.as_bytes();
+        let length = items.len() / 2;
+        result += [
+            Rucksack::new(&items[..length]),
+            Rucksack::new(&items[length..]),
+        ]
+        .score();
+    }
 
     Ok(result)
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let result = input
+pub fn part_b(rucksacks: &Rucksacks) -> anyhow::Result<impl Into<Answer>> {
+    let rucksacks = &rucksacks.0;
+
+    if !rucksacks.len().is_multiple_of(3) {
+        bail!(
+            "{} rucksacks cannot be split into groups of three elves",
+            rucksacks.len()
+        );
+    }
+
+    let result = rucksacks
         .chunks_exact(3)
         .map(|chunk| {
             std::array::from_fn::<_, 3, _>(|index| Rucksack::new(chunk[index].as_bytes())).score()
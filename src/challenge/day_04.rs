@@ -1,18 +1,37 @@
-use anyhow::Context;
-use std::str::FromStr;
+use super::{register, Answer, Day, LineParser, Parameters, ParseError};
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(solve(input, Range::overlaps))
+register! {
+    day: Day04,
+    title: "Camp Cleanup",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(solve(input, Range::partially_overlaps))
+pub struct Pairs(Vec<Pair>);
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Pairs> {
+    let pairs = input
+        .iter()
+        .enumerate()
+        .map(|(index, line)| Pair::parse(index, line))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Pairs(pairs))
 }
 
-fn solve(input: &[&str], cond: fn(&Range, &Range) -> bool) -> usize {
-    input
+pub fn part_a(pairs: &Pairs) -> anyhow::Result<impl Into<Answer>> {
+    Ok(count(pairs, Range::overlaps))
+}
+
+pub fn part_b(pairs: &Pairs) -> anyhow::Result<impl Into<Answer>> {
+    Ok(count(pairs, Range::partially_overlaps))
+}
+
+fn count(pairs: &Pairs, cond: fn(&Range, &Range) -> bool) -> usize {
+    pairs
+        .0
         .iter()
-        .filter_map(|line| Pair::from_str(line).ok())
         .filter(|pair| pair.check_condition(cond))
         .count()
 }
@@ -20,20 +39,17 @@ fn solve(input: &[&str], cond: fn(&Range, &Range) -> bool) -> usize {
 struct Pair(Range, Range);
 
 impl Pair {
-    fn check_condition(&self, cond: fn(&Range, &Range) -> bool) -> bool {
-        cond(&self.0, &self.1) || cond(&self.1, &self.0)
+    fn parse(index: usize, line: &str) -> Result<Self, ParseError> {
+        let mut parser = LineParser::new(Day::Day04, index, line);
+        let first = Range::parse(&mut parser)?;
+        parser.expect(",")?;
+        let second = Range::parse(&mut parser)?;
+        parser.end()?;
+        Ok(Pair(first, second))
     }
-}
-
-impl FromStr for Pair {
-    type Err = anyhow::Error;
 
-    fn from_str(pair: &str) -> Result<Self, Self::Err> {
-        let (first, second) = pair
-            .split_once(',')
-            .with_context(|| format!("{} is not a valid pair", pair))?;
-
-        Ok(Pair(first.parse()?, second.parse()?))
+    fn check_condition(&self, cond: fn(&Range, &Range) -> bool) -> bool {
+        cond(&self.0, &self.1) || cond(&self.1, &self.0)
     }
 }
 
@@ -43,6 +59,13 @@ struct Range {
 }
 
 impl Range {
+    fn parse(parser: &mut LineParser) -> Result<Self, ParseError> {
+        let start = parser.number()?;
+        parser.expect("-")?;
+        let end = parser.number()?;
+        Ok(Range { start, end })
+    }
+
     fn overlaps(&self, other: &Self) -> bool {
         self.start >= other.start && self.end <= other.end
     }
@@ -51,20 +74,3 @@ impl Range {
         self.start >= other.start && self.start <= other.end
     }
 }
-
-impl FromStr for Range {
-    type Err = anyhow::Error;
-
-    fn from_str(range: &str) -> Result<Self, Self::Err> {
-        let (start, end) = range
-            .split_once('-')
-            .with_context(|| format!("{} is not a valid range", range))?;
-
-        let result = Range {
-            start: start.parse()?,
-            end: end.parse()?,
-        };
-
-        Ok(result)
-    }
-}
@@ -1,25 +1,53 @@
-use anyhow::Context;
+use super::{register, Answer, Day, LineParser, Parameters, ParseError};
+use anyhow::{bail, Context};
 use std::cmp::Ordering;
-use std::str::FromStr;
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    solve(input, &CrateMover9000)
+register! {
+    day: Day05,
+    title: "Supply Stacks",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    solve(input, &CrateMover9001)
+// Drawing of the stacks, followed by the rearrangement procedure
+pub struct Procedure {
+    stacks: Stacks,
+    instructions: Vec<Instruction>,
 }
 
-fn solve(input: &[&str], crane: &impl Crane) -> anyhow::Result<impl std::fmt::Display> {
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Procedure> {
     let index = input
         .iter()
         .position(|line| line.is_empty())
         .context("Input doesn't contain a new line")?;
 
-    let mut stacks = Stacks::new(&input[..index]);
+    let stacks = Stacks::parse(&input[..index])?;
+    let instructions = input[index + 1..]
+        .iter()
+        .enumerate()
+        .map(|(offset, line)| Instruction::parse(index + 1 + offset, line, stacks.0.len()))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Procedure {
+        stacks,
+        instructions,
+    })
+}
+
+pub fn part_a(procedure: &Procedure) -> anyhow::Result<impl Into<Answer>> {
+    solve(procedure, &CrateMover9000)
+}
+
+pub fn part_b(procedure: &Procedure) -> anyhow::Result<impl Into<Answer>> {
+    solve(procedure, &CrateMover9001)
+}
+
+fn solve(procedure: &Procedure, crane: &impl Crane) -> anyhow::Result<impl Into<Answer>> {
+    let mut stacks = procedure.stacks.clone();
 
-    for line in &input[index + 1..] {
-        crane.move_crates(&mut stacks, line.parse()?);
+    for instruction in &procedure.instructions {
+        crane.move_crates(&mut stacks, instruction)?;
     }
 
     let result = stacks
@@ -38,47 +66,72 @@ struct Instruction {
     to: usize,
 }
 
-impl FromStr for Instruction {
-    type Err = anyhow::Error;
+impl Instruction {
+    // Stacks are numbered from 1 in the input and from 0 once parsed
+    fn parse(index: usize, line: &str, stacks: usize) -> Result<Self, ParseError> {
+        let mut parser = LineParser::new(Day::Day05, index, line);
+        let stack = |parser: &mut LineParser| {
+            let position = parser.position();
 
-    fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let mut iterator = line.split_ascii_whitespace().skip(1).step_by(2);
-        let count = next_number(&mut iterator, line)?;
-        let from = next_number(&mut iterator, line)? - 1;
-        let to = next_number(&mut iterator, line)? - 1;
-        Ok(Instruction { count, from, to })
-    }
-}
+            match parser.number::<usize>()? {
+                number @ 1.. if number <= stacks => Ok(number - 1),
+                _ => Err(parser.error_at(position, format!("a stack from 1 to {}", stacks))),
+            }
+        };
 
-fn next_number<'a>(
-    iterator: &mut impl Iterator<Item = &'a str>,
-    source: &str,
-) -> anyhow::Result<usize> {
-    let number = iterator
-        .next()
-        .with_context(|| format!("{} in not a valid instruction", source))?
-        .parse()?;
+        parser.expect("move ")?;
+        let count = parser.number()?;
+        parser.expect(" from ")?;
+        let from = stack(&mut parser)?;
+        parser.expect(" to ")?;
+        let to = stack(&mut parser)?;
+        parser.end()?;
 
-    Ok(number)
+        Ok(Instruction { count, from, to })
+    }
 }
 
+#[derive(Clone)]
 struct Stacks(Box<[Vec<u8>]>);
 
 impl Stacks {
-    fn new(lines: &[&str]) -> Self {
-        let columns = (lines[0].len() + 1) / 4;
+    // Reads the drawing of the stacks from the bottom up, its last line numbers the stacks
+    fn parse(lines: &[&str]) -> anyhow::Result<Self> {
+        let (numbers, crates) = lines
+            .split_last()
+            .context("The drawing of the stacks is missing")?;
+
+        let columns = numbers.split_ascii_whitespace().count();
         let mut stacks = (0..columns).map(|_| Vec::new()).collect::<Box<[_]>>();
 
-        for line in lines.iter().rev().skip(1) {
-            line.bytes()
-                .skip(1)
-                .step_by(4)
-                .enumerate()
-                .filter(|(_, byte)| *byte != b' ')
-                .for_each(|(i, byte)| stacks[i].push(byte));
+        for (index, line) in crates.iter().enumerate().rev() {
+            let parser = LineParser::new(Day::Day05, index, line);
+
+            // Crates are found by their offset, which only works for ASCII
+            if let Some(position) = line.find(|char: char| !char.is_ascii()) {
+                return Err(parser.error_at(position, "a crate").into());
+            }
+
+            for (i, position) in (1..line.len()).step_by(4).enumerate() {
+                let byte = line.as_bytes()[position];
+
+                if byte == b' ' {
+                    continue;
+                }
+
+                if i >= columns {
+                    return Err(parser.error_at(position, "the end of the line").into());
+                }
+
+                if !byte.is_ascii_uppercase() {
+                    return Err(parser.error_at(position, "a crate").into());
+                }
+
+                stacks[i].push(byte);
+            }
         }
 
-        Stacks(stacks)
+        Ok(Stacks(stacks))
     }
 
     fn borrow_stacks(
@@ -104,21 +157,42 @@ struct CrateMover9000;
 struct CrateMover9001;
 
 trait Crane {
-    fn move_crates(&self, stacks: &mut Stacks, instruction: Instruction);
+    fn move_crates(&self, stacks: &mut Stacks, instruction: &Instruction) -> anyhow::Result<()>;
 }
 
 impl Crane for CrateMover9000 {
-    fn move_crates(&self, stacks: &mut Stacks, instruction: Instruction) {
+    fn move_crates(&self, stacks: &mut Stacks, instruction: &Instruction) -> anyhow::Result<()> {
         if let Some((from, to)) = stacks.borrow_stacks(instruction.from, instruction.to) {
-            to.extend(from.drain(from.len() - instruction.count..).rev());
+            to.extend(take(from, instruction)?.rev());
         }
+
+        Ok(())
     }
 }
 
 impl Crane for CrateMover9001 {
-    fn move_crates(&self, stacks: &mut Stacks, instruction: Instruction) {
+    fn move_crates(&self, stacks: &mut Stacks, instruction: &Instruction) -> anyhow::Result<()> {
         if let Some((from, to)) = stacks.borrow_stacks(instruction.from, instruction.to) {
-            to.extend(from.drain(from.len() - instruction.count..));
+            to.extend(take(from, instruction)?);
         }
+
+        Ok(())
     }
 }
+
+// Removes the top crates of the stack, which has to hold enough of them
+fn take<'a>(
+    stack: &'a mut Vec<u8>,
+    instruction: &Instruction,
+) -> anyhow::Result<std::vec::Drain<'a, u8>> {
+    if stack.len() < instruction.count {
+        bail!(
+            "Cannot move {} crates from stack {}, which holds {}",
+            instruction.count,
+            instruction.from + 1,
+            stack.len()
+        );
+    }
+
+    Ok(stack.drain(stack.len() - instruction.count..))
+}
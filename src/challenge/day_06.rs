@@ -1,12 +1,49 @@
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(solve(input[0].as_bytes(), 4))
+use super::{register, Answer, Day, LineParser, Parameters};
+use anyhow::Context;
+
+register! {
+    day: Day06,
+    title: "Tuning Trouble",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
+// The datastream is a single line of lowercase letters
+pub struct Datastream(Vec<u8>);
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Datastream> {
+    let line = match input {
+        [line] => line,
+        [] => {
+            return Err(LineParser::new(Day::Day06, 0, "")
+                .error("a datastream")
+                .into())
+        }
+        [_, extra, ..] => {
+            let parser = LineParser::new(Day::Day06, 1, extra);
+            return Err(parser.error("the end of the input").into());
+        }
+    };
+
+    match line.find(|char: char| !char.is_ascii_lowercase()) {
+        Some(position) => {
+            let parser = LineParser::new(Day::Day06, 0, line);
+            Err(parser.error_at(position, "a lowercase letter").into())
+        }
+        None => Ok(Datastream(line.as_bytes().to_vec())),
+    }
+}
+
+pub fn part_a(datastream: &Datastream) -> anyhow::Result<impl Into<Answer>> {
+    solve(&datastream.0, 4)
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(solve(input[0].as_bytes(), 14))
+pub fn part_b(datastream: &Datastream) -> anyhow::Result<impl Into<Answer>> {
+    solve(&datastream.0, 14)
 }
 
-fn solve(sequence: &[u8], length: usize) -> usize {
+fn solve(sequence: &[u8], length: usize) -> anyhow::Result<usize> {
     let result = sequence
         .windows(length)
         .position(|bytes| {
@@ -17,7 +54,7 @@ fn solve(sequence: &[u8], length: usize) -> usize {
                 .count_ones() as usize
                 == length
         })
-        .unwrap();
+        .with_context(|| format!("No {} characters in a row are all different", length))?;
 
-    result + length
+    Ok(result + length)
 }
@@ -1,46 +1,110 @@
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let result = compute_directory_sizes(input)
-        .into_iter()
-        .filter(|size| *size <= 100000)
+use super::{register, Answer, Day, LineParser, Parameters, ParseError};
+use anyhow::Context;
+
+register! {
+    day: Day07,
+    title: "No Space Left On Device",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
+// Total size of every directory, starting with the root directory
+pub struct Directories(Vec<usize>);
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Directories> {
+    Ok(Directories(compute_directory_sizes(input)?))
+}
+
+pub fn part_a(directories: &Directories) -> anyhow::Result<impl Into<Answer>> {
+    let result = directories
+        .0
+        .iter()
+        .filter(|size| **size <= 100000)
         .sum::<usize>();
 
     Ok(result)
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let directories = compute_directory_sizes(input);
-    let min_size = directories[0] - 40000000; // (+ 30000000 - 70000000)
+pub fn part_b(directories: &Directories) -> anyhow::Result<impl Into<Answer>> {
+    let directories = &directories.0;
+    let used = *directories.first().context("No directories were listed")?;
+    let min_size = used.saturating_sub(40000000); // (+ 30000000 - 70000000)
 
     let result = directories
-        .into_iter()
+        .iter()
+        .copied()
         .filter(|size| *size >= min_size)
         .min()
-        .unwrap();
+        .unwrap_or_default();
 
     Ok(result)
 }
 
-// Turns out the commands do a DFS traversal of the file system
-fn compute_directory_sizes(input: &[&str]) -> Vec<usize> {
+enum Line<'a> {
+    ChangeDirectory(&'a str),
+    List,
+    Directory,
+    File(usize),
+}
+
+impl<'a> Line<'a> {
+    fn parse(index: usize, line: &'a str) -> Result<Self, ParseError> {
+        let mut parser = LineParser::new(Day::Day07, index, line);
+
+        let parsed = if line.starts_with('$') {
+            parser.expect("$ ")?;
+
+            match parser.word()? {
+                "cd" => {
+                    parser.expect(" ")?;
+                    Line::ChangeDirectory(parser.word()?)
+                }
+                "ls" => Line::List,
+                _ => return Err(parser.error_at(2, "cd or ls")),
+            }
+        } else if line.starts_with("dir") {
+            parser.expect("dir ")?;
+            parser.word()?;
+            Line::Directory
+        } else {
+            let size = parser.number()?;
+            parser.expect(" ")?;
+            parser.word()?;
+            Line::File(size)
+        };
+
+        parser.end()?;
+        Ok(parsed)
+    }
+}
+
+// Turns out the commands do a DFS traversal of the file system, the root directory comes first
+fn compute_directory_sizes(input: &[&str]) -> Result<Vec<usize>, ParseError> {
     let mut working_directory = Vec::with_capacity(16);
     let mut directory_sizes = Vec::with_capacity(200);
 
-    for line in input {
-        if line.starts_with(|char: char| char.is_ascii_digit()) {
-            let size = line.split_once(' ').unwrap().0.parse::<usize>().unwrap();
-
-            for &i in &working_directory {
-                directory_sizes[i] += size;
+    for (index, line) in input.iter().enumerate() {
+        match Line::parse(index, line)? {
+            Line::File(size) => {
+                for &i in &working_directory {
+                    directory_sizes[i] += size;
+                }
             }
-        } else if line.starts_with("$ cd") {
-            if &line[5..] == ".." {
+            Line::ChangeDirectory("..") => {
                 working_directory.pop();
-            } else {
+            }
+            Line::ChangeDirectory("/") if !directory_sizes.is_empty() => {
+                working_directory.clear();
+                working_directory.push(0);
+            }
+            Line::ChangeDirectory(_) => {
                 working_directory.push(directory_sizes.len());
                 directory_sizes.push(0);
             }
+            Line::List | Line::Directory => {}
         }
     }
 
-    directory_sizes
+    Ok(directory_sizes)
 }
@@ -1,15 +1,70 @@
+use super::{register, Answer, Day, LineParser, Parameters, ParseError};
+
+register! {
+    day: Day08,
+    title: "Treetop Tree House",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
 const VISITED_MASK: u8 = 1 << 7;
+// Assume the best spot has at least this height
+const MIN_HEIGHT: u8 = 6;
+
+// Trees along with the height which the best spot is assumed to have
+pub struct Forest {
+    grid: Grid,
+    min_height: u8,
+}
+
+pub fn parse(input: &[&str], parameters: &Parameters) -> anyhow::Result<Forest> {
+    Ok(Forest {
+        grid: parse_grid(input)?,
+        min_height: parameters.get("min_height", MIN_HEIGHT)?,
+    })
+}
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(TreeCounter::new(input).count())
+pub fn part_a(forest: &Forest) -> anyhow::Result<impl Into<Answer>> {
+    Ok(TreeCounter::new(&forest.grid).count())
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(SpotSelector::new(input).select(6))
+pub fn part_b(forest: &Forest) -> anyhow::Result<impl Into<Answer>> {
+    Ok(SpotSelector::new(&forest.grid).select(forest.min_height))
 }
 
-fn parse_grid(input: &[&str]) -> Vec<u8> {
-    Vec::from_iter(input.iter().flat_map(|line| line.bytes()).map(|x| x - b'0'))
+// Heights of the trees in a square grid, row by row
+struct Grid {
+    size: usize,
+    trees: Vec<u8>,
+}
+
+fn parse_grid(input: &[&str]) -> Result<Grid, ParseError> {
+    let size = input.len();
+
+    if size == 0 {
+        return Err(LineParser::new(Day::Day08, 0, "").error("a row of trees"));
+    }
+
+    let mut trees = Vec::with_capacity(size * size);
+
+    for (index, line) in input.iter().enumerate() {
+        let parser = LineParser::new(Day::Day08, index, line);
+
+        if let Some(position) = line.find(|char: char| !char.is_ascii_digit()) {
+            return Err(parser.error_at(position, "the height of a tree"));
+        }
+
+        // The grid is as wide as it is tall
+        if line.len() != size {
+            let position = line.len().min(size);
+            return Err(parser.error_at(position, format!("a row of {} trees", size)));
+        }
+
+        trees.extend(line.bytes().map(|height| height - b'0'));
+    }
+
+    Ok(Grid { size, trees })
 }
 
 struct TreeCounter {
@@ -20,12 +75,13 @@ struct TreeCounter {
 }
 
 impl TreeCounter {
-    fn new(input: &[&str]) -> Self {
+    fn new(grid: &Grid) -> Self {
         TreeCounter {
-            count: 4 * (input.len() - 1),
+            // Trees on the edge are all visible, which is the only one in a grid of one
+            count: (4 * (grid.size - 1)).max(1),
             max_height: 0,
-            size: input.len(),
-            trees: parse_grid(input),
+            size: grid.size,
+            trees: grid.trees.clone(),
         }
     }
 
@@ -75,18 +131,16 @@ impl TreeCounter {
     }
 }
 
-struct SpotSelector {
+struct SpotSelector<'a> {
     size: usize,
-    trees: Vec<u8>,
+    trees: &'a [u8],
 }
 
-impl SpotSelector {
-    fn new(input: &[&str]) -> Self {
-        let grid = parse_grid(input);
-
+impl<'a> SpotSelector<'a> {
+    fn new(grid: &'a Grid) -> Self {
         SpotSelector {
-            size: input.len(),
-            trees: grid,
+            size: grid.size,
+            trees: &grid.trees,
         }
     }
 
@@ -1,15 +1,52 @@
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(simulate::<2>(input))
+use super::{register, Answer, Day, LineParser, Parameters};
+
+register! {
+    day: Day09,
+    title: "Rope Bridge",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
+// Direction and number of steps of every motion of the head
+pub struct Motions(Vec<(Direction, u16)>);
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Motions> {
+    let motions = input
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let mut parser = LineParser::new(Day::Day09, index, line);
+
+            let direction = match parser.one_of(&['U', 'D', 'L', 'R'])? {
+                'U' => Direction::Up,
+                'D' => Direction::Down,
+                'L' => Direction::Left,
+                _ => Direction::Right,
+            };
+
+            parser.expect(" ")?;
+            let steps = parser.number()?;
+            parser.end()?;
+            Ok((direction, steps))
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(Motions(motions))
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(simulate::<10>(input))
+pub fn part_a(motions: &Motions) -> anyhow::Result<impl Into<Answer>> {
+    Ok(simulate::<2>(&motions.0))
+}
+
+pub fn part_b(motions: &Motions) -> anyhow::Result<impl Into<Answer>> {
+    Ok(simulate::<10>(&motions.0))
 }
 
 #[derive(Default, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
 struct Position {
-    x: i16,
-    y: i16,
+    x: i32,
+    y: i32,
 }
 
 enum Direction {
@@ -19,20 +56,12 @@ enum Direction {
     Right,
 }
 
-fn simulate<const N: usize>(input: &[&str]) -> usize {
+fn simulate<const N: usize>(motions: &[(Direction, u16)]) -> usize {
     let mut knots = [Position::default(); N];
     let mut positions = vec![Position::default()];
 
-    for line in input {
-        let direction = match line.as_bytes()[0] {
-            b'U' => Direction::Up,
-            b'D' => Direction::Down,
-            b'L' => Direction::Left,
-            b'R' => Direction::Right,
-            _ => unreachable!(),
-        };
-
-        for _ in 0..line[2..].parse::<i16>().unwrap() {
+    for (direction, steps) in motions {
+        for _ in 0..*steps {
             match direction {
                 Direction::Up => knots[0].y += 1,
                 Direction::Down => knots[0].y -= 1,
@@ -1,16 +1,38 @@
+use super::{register, Answer, Day, LineParser, Parameters, ParseError};
+use anyhow::bail;
 use std::cmp::Ordering;
 
+register! {
+    day: Day10,
+    title: "Cathode-Ray Tube",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
 const CYCLES: [usize; 6] = [20, 60, 100, 140, 180, 220];
 const SCREEN_WIDTH: usize = 40;
 const SCREEN_HEIGHT: usize = 6;
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
+pub struct Program(Vec<Instruction>);
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Program> {
+    let instructions = input
+        .iter()
+        .enumerate()
+        .map(|(index, line)| Instruction::parse(index, line))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Program(instructions))
+}
+
+pub fn part_a(program: &Program) -> anyhow::Result<impl Into<Answer>> {
     let mut i = 0;
     let mut cycle = 0usize;
     let mut register = 1isize;
     let mut result = 0isize;
 
-    for instruction in input {
+    for &instruction in &program.0 {
         let value = register;
         execute(instruction, &mut cycle, &mut register);
 
@@ -22,49 +44,79 @@ pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
         i += 1;
 
         if i == CYCLES.len() {
-            break;
+            return Ok(result);
         }
     }
 
-    Ok(result)
+    bail!(
+        "The program ends after {} cycles, before cycle {}",
+        cycle,
+        CYCLES[i]
+    )
+}
+
+#[derive(Copy, Clone)]
+enum Instruction {
+    Noop,
+    Addx(isize),
+}
+
+impl Instruction {
+    fn parse(index: usize, line: &str) -> Result<Self, ParseError> {
+        let mut parser = LineParser::new(Day::Day10, index, line);
+
+        let instruction = match parser.word()? {
+            "noop" => Instruction::Noop,
+            "addx" => {
+                parser.expect(" ")?;
+                Instruction::Addx(parser.number()?)
+            }
+            _ => return Err(parser.error_at(0, "noop or addx")),
+        };
+
+        parser.end()?;
+        Ok(instruction)
+    }
 }
 
-fn execute(instruction: &str, cycle: &mut usize, register: &mut isize) {
-    match instruction.as_bytes()[0] {
-        b'n' => {
+fn execute(instruction: Instruction, cycle: &mut usize, register: &mut isize) {
+    match instruction {
+        Instruction::Noop => {
             *cycle += 1;
         }
-        b'a' => {
+        Instruction::Addx(value) => {
             *cycle += 2;
-            *register += instruction[5..].parse::<isize>().unwrap();
+            *register += value;
         }
-        _ => unreachable!(),
     }
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
+pub fn part_b(program: &Program) -> anyhow::Result<impl Into<Answer>> {
     let mut screen = String::with_capacity(SCREEN_WIDTH * SCREEN_HEIGHT);
     let mut position = 1isize;
 
-    for instruction in input {
-        match instruction.as_bytes()[0] {
-            b'n' => {
+    for instruction in &program.0 {
+        match *instruction {
+            Instruction::Noop => {
                 draw(position, &mut screen);
             }
-            b'a' => {
+            Instruction::Addx(value) => {
                 draw(position, &mut screen);
                 draw(position, &mut screen);
-                position += instruction[5..].parse::<isize>()?;
+                position += value;
             }
-            _ => unreachable!(),
         }
     }
 
-    for i in 0..SCREEN_HEIGHT {
-        println!("{}", &screen[i * SCREEN_WIDTH..(i + 1) * SCREEN_WIDTH]);
+    if screen.len() < SCREEN_WIDTH * SCREEN_HEIGHT {
+        bail!(
+            "The program ends after {} cycles, before the screen is drawn",
+            screen.len()
+        );
     }
 
-    Ok("picture")
+    let rows = (0..SCREEN_HEIGHT).map(|i| &screen[i * SCREEN_WIDTH..(i + 1) * SCREEN_WIDTH]);
+    Answer::letters(rows)
 }
 
 fn draw(position: isize, screen: &mut String) {
@@ -1,25 +1,44 @@
+use super::{register, Answer, Day, LineParser, Parameters, ParseError};
+use anyhow::{bail, Context};
 use std::collections::VecDeque;
 
-const MONKEY_LINE_LENGTH: usize = 7;
+register! {
+    day: Day11,
+    title: "Monkey in the Middle",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
+// Lines describing a monkey, not counting the empty line between monkeys
+const MONKEY_LINE_LENGTH: usize = 6;
+
+pub struct Monkeys(Vec<Monkey>);
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Monkeys> {
+    Ok(Monkeys(parse_monkeys(input)?))
+}
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(solve(input, 20, true))
+pub fn part_a(monkeys: &Monkeys) -> anyhow::Result<impl Into<Answer>> {
+    solve(monkeys, 20, true)
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(solve(input, 10000, false))
+pub fn part_b(monkeys: &Monkeys) -> anyhow::Result<impl Into<Answer>> {
+    solve(monkeys, 10000, false)
 }
 
-fn solve(input: &[&str], rounds: usize, relief: bool) -> usize {
-    let length = (input.len() + 1) / MONKEY_LINE_LENGTH;
-    let mut monkeys = Vec::with_capacity(length);
+fn solve(monkeys: &Monkeys, rounds: usize, relief: bool) -> anyhow::Result<usize> {
+    let mut monkeys = monkeys.0.clone();
+    let length = monkeys.len();
 
-    for i in 0..length {
-        let input = &input[i * MONKEY_LINE_LENGTH..(i + 1) * MONKEY_LINE_LENGTH - 1];
-        monkeys.push(Monkey::parse(input));
+    if length < 2 {
+        bail!("Monkey business needs at least two monkeys");
     }
 
-    let modulo = monkeys.iter().fold(1, |acc, monkey| acc * monkey.divisor);
+    let modulo = monkeys
+        .iter()
+        .try_fold(1u64, |acc, monkey| acc.checked_mul(monkey.divisor))
+        .context("The divisors of the monkeys are too large")?;
 
     for _ in 0..rounds {
         for i in 0..length {
@@ -35,7 +54,42 @@ fn solve(input: &[&str], rounds: usize, relief: bool) -> usize {
         .collect::<Vec<usize>>();
 
     counts.sort();
-    counts[length - 1] * counts[length - 2]
+    Ok(counts[length - 1] * counts[length - 2])
+}
+
+// Monkeys are separated by empty lines. Every monkey has to throw to another existing one, a
+// monkey throwing to itself would never run out of items.
+fn parse_monkeys(input: &[&str]) -> Result<Vec<Monkey>, ParseError> {
+    let mut monkeys = Vec::new();
+    let mut targets = Vec::new();
+    let mut index = 0;
+
+    while index < input.len() {
+        if input[index].is_empty() {
+            index += 1;
+            continue;
+        }
+
+        let (monkey, positions) = Monkey::parse(input, index)?;
+
+        for ((line, position), target) in
+            positions.into_iter().zip([monkey.if_true, monkey.if_false])
+        {
+            targets.push((monkeys.len(), line, position, target));
+        }
+
+        monkeys.push(monkey);
+        index += MONKEY_LINE_LENGTH;
+    }
+
+    for (owner, line, position, target) in targets {
+        if target >= monkeys.len() || target == owner {
+            let parser = LineParser::new(Day::Day11, line, input[line]);
+            return Err(parser.error_at(position, "another monkey"));
+        }
+    }
+
+    Ok(monkeys)
 }
 
 #[derive(Debug)]
@@ -44,7 +98,7 @@ struct Throw {
     item: u64,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct Monkey {
     items: VecDeque<u64>,
     operation: Operation,
@@ -55,19 +109,76 @@ struct Monkey {
 }
 
 impl Monkey {
-    fn parse(input: &[&str]) -> Self {
-        let items = input[1][17..]
-            .split(',')
-            .map(|item| item[1..].parse::<u64>().unwrap());
-
-        Monkey {
-            items: VecDeque::from_iter(items),
-            operation: Operation::parse(&input[2][23..]),
-            divisor: input[3][21..].parse::<u64>().unwrap(),
-            if_true: input[4][29..].parse::<usize>().unwrap(),
-            if_false: input[5][30..].parse::<usize>().unwrap(),
-            throw_count: 0,
+    // Reads the monkey starting at the given line, along with the line and position of the monkeys
+    // it throws to, which can only be checked once every monkey is known
+    fn parse(input: &[&str], index: usize) -> Result<(Self, [(usize, usize); 2]), ParseError> {
+        let line = |offset: usize| {
+            let index = index + offset;
+            LineParser::new(
+                Day::Day11,
+                index,
+                input.get(index).copied().unwrap_or_default(),
+            )
+        };
+
+        let mut parser = line(0);
+        parser.expect("Monkey ")?;
+        parser.number::<usize>()?;
+        parser.expect(":")?;
+        parser.end()?;
+
+        let mut parser = line(1);
+        let mut items = VecDeque::new();
+        parser.expect("  Starting items:")?;
+
+        if !parser.is_empty() {
+            parser.expect(" ")?;
+            items.push_back(parser.number()?);
+
+            while !parser.is_empty() {
+                parser.expect(", ")?;
+                items.push_back(parser.number()?);
+            }
         }
+
+        let mut parser = line(2);
+        parser.expect("  Operation: new = old ")?;
+        let operation = Operation::parse(&mut parser)?;
+        parser.end()?;
+
+        let mut parser = line(3);
+        parser.expect("  Test: divisible by ")?;
+        let position = parser.position();
+
+        let divisor = match parser.number()? {
+            0 => return Err(parser.error_at(position, "a divisor")),
+            divisor => divisor,
+        };
+
+        parser.end()?;
+
+        let target = |offset: usize, prefix: &str| {
+            let mut parser = line(offset);
+            parser.expect(prefix)?;
+            let position = parser.position();
+            let monkey = parser.number()?;
+            parser.end()?;
+            Ok::<_, ParseError>((monkey, (index + offset, position)))
+        };
+
+        let (if_true, true_position) = target(4, "    If true: throw to monkey ")?;
+        let (if_false, false_position) = target(5, "    If false: throw to monkey ")?;
+
+        let monkey = Monkey {
+            items,
+            operation,
+            divisor,
+            if_true,
+            if_false,
+            throw_count: 0,
+        };
+
+        Ok((monkey, [true_position, false_position]))
     }
 
     fn throw_next_item(&mut self, relief: bool, modulo: u64) -> Option<Throw> {
@@ -75,7 +186,7 @@ impl Monkey {
             .operation
             .calculate_worry_level(self.items.pop_front()?, relief, modulo);
 
-        let monkey = if item % self.divisor == 0 {
+        let monkey = if item.is_multiple_of(self.divisor) {
             self.if_true
         } else {
             self.if_false
@@ -91,7 +202,7 @@ impl Monkey {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 enum Operation {
     Add(u64),
     Multiply(u64),
@@ -99,18 +210,26 @@ enum Operation {
 }
 
 impl Operation {
-    fn parse(input: &str) -> Self {
-        if input.as_bytes()[2] == b'o' {
-            return Operation::Square;
+    // Reads the rest of a line such as "new = old * 19", after the first old
+    fn parse(parser: &mut LineParser) -> Result<Self, ParseError> {
+        let operator = parser.one_of(&['+', '*'])?;
+        parser.expect(" ")?;
+
+        if parser.remaining() == "old" {
+            parser.expect("old")?;
+
+            return Ok(match operator {
+                '+' => Operation::Multiply(2),
+                _ => Operation::Square,
+            });
         }
 
-        let value = input[2..].parse::<u64>().unwrap();
+        let value = parser.number()?;
 
-        match input.as_bytes()[0] {
-            b'+' => Operation::Add(value),
-            b'*' => Operation::Multiply(value),
-            _ => unreachable!(),
-        }
+        Ok(match operator {
+            '+' => Operation::Add(value),
+            _ => Operation::Multiply(value),
+        })
     }
 
     fn calculate_worry_level(&self, item: u64, relief: bool, modulo: u64) -> u64 {
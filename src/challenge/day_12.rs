@@ -1,10 +1,22 @@
+use super::{register, Answer, Day, LineParser, Parameters};
 use anyhow::{bail, Context};
 use std::collections::VecDeque;
 use std::ops::{Index, IndexMut};
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let map = Map::new(input);
-    let scores = find_path(&map, Direction::Up);
+register! {
+    day: Day12,
+    title: "Hill Climbing Algorithm",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Map> {
+    Map::parse(input)
+}
+
+pub fn part_a(map: &Map) -> anyhow::Result<impl Into<Answer>> {
+    let scores = find_path(map, Direction::Up);
 
     match scores[map.end] {
         u16::MAX => bail!("Could not find path"),
@@ -12,9 +24,8 @@ pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
     }
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let map = Map::new(input);
-    let scores = find_path(&map, Direction::Down);
+pub fn part_b(map: &Map) -> anyhow::Result<impl Into<Answer>> {
+    let scores = find_path(map, Direction::Down);
 
     let score = map
         .grid
@@ -92,8 +103,6 @@ struct Position {
 }
 
 impl Position {
-    const MIN: Position = Position::new(0, 0);
-
     const fn new(x: u16, y: u16) -> Self {
         Position { x, y }
     }
@@ -119,7 +128,7 @@ enum Direction {
     Down,
 }
 
-struct Map {
+pub struct Map {
     width: u16,
     height: u16,
     start: Position,
@@ -128,36 +137,65 @@ struct Map {
 }
 
 impl Map {
-    fn new(input: &[&str]) -> Self {
-        let width = input[0].len() as u16;
-        let height = input.len() as u16;
+    // Heights are lowercase letters, apart from the start S and the end E. Paths are counted with
+    // 16 bits, which is plenty for the maps of the puzzle.
+    fn parse(input: &[&str]) -> anyhow::Result<Self> {
+        let width = input.first().map_or(0, |line| line.len());
+        let height = input.len();
+
+        if width * height >= u16::MAX as usize {
+            bail!(
+                "The map is too large, it has {} by {} squares",
+                width,
+                height
+            );
+        }
 
-        let mut start = Position::MIN;
-        let mut end = Position::MIN;
-        let mut grid = vec![Vec::with_capacity(width as usize); height as usize];
+        let mut start = None;
+        let mut end = None;
+        let mut grid = Vec::with_capacity(height);
 
         for (i, line) in input.iter().enumerate() {
-            let row = line.as_bytes();
-            grid[i].extend_from_slice(row);
-
-            if let Some(offset) = row.iter().position(|x| *x == b'S') {
-                start = Position::new(offset as u16, i as u16);
-                grid[i][offset] = b'a';
+            let parser = LineParser::new(Day::Day12, i, line);
+            let mut row = Vec::with_capacity(width);
+
+            for (offset, char) in line.char_indices() {
+                let position = Position::new(offset as u16, i as u16);
+
+                let (marker, height) = match char {
+                    'a'..='z' => (None, char as u8),
+                    'S' => (Some(&mut start), b'a'),
+                    'E' => (Some(&mut end), b'z'),
+                    _ => return Err(parser.error_at(offset, "a height, S or E").into()),
+                };
+
+                if let Some(marker) = marker {
+                    if marker.replace(position).is_some() {
+                        return Err(parser.error_at(offset, "a single S and E").into());
+                    }
+                }
+
+                row.push(height);
             }
 
-            if let Some(offset) = row.iter().position(|x| *x == b'E') {
-                end = Position::new(offset as u16, i as u16);
-                grid[i][offset] = b'z';
+            // Every row is as wide as the first one
+            if row.len() != width {
+                let position = line.len().min(width);
+                return Err(parser
+                    .error_at(position, format!("a row of {} squares", width))
+                    .into());
             }
+
+            grid.push(row);
         }
 
-        Map {
-            width,
-            height,
-            start,
-            end,
+        Ok(Map {
+            width: width as u16,
+            height: height as u16,
+            start: start.context("The map does not have a start")?,
+            end: end.context("The map does not have an end")?,
             grid,
-        }
+        })
     }
 
     fn fill_neighbors(&self, position: Position, direction: Direction, buffer: &mut Vec<Position>) {
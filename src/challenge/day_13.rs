@@ -1,8 +1,37 @@
+use super::{register, Answer, Day, LineParser, Parameters, ParseError};
+use anyhow::bail;
 use std::cmp::Ordering;
-use std::iter::Peekable;
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let result = parse(input)
+register! {
+    day: Day13,
+    title: "Distress Signal",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
+// Packets in the order of the input, leaving out the empty lines between pairs
+pub struct Signals(Vec<Signal>);
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Signals> {
+    let signals = input
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(index, line)| parse_signal(index, line))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Signals(signals))
+}
+
+pub fn part_a(signals: &Signals) -> anyhow::Result<impl Into<Answer>> {
+    let signals = &signals.0;
+
+    if !signals.len().is_multiple_of(2) {
+        bail!("The last packet does not have a pair");
+    }
+
+    let result = signals
         .chunks(2)
         .enumerate()
         .filter(|(_, chunk)| chunk[0].cmp(&chunk[1]) == Ordering::Less)
@@ -12,10 +41,10 @@ pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
     Ok(result)
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
+pub fn part_b(signals: &Signals) -> anyhow::Result<impl Into<Answer>> {
     let divider_2 = Signal::divider(2);
     let divider_6 = Signal::divider(6);
-    let signals = parse(input);
+    let signals = &signals.0;
 
     let first = signals
         .iter()
@@ -32,8 +61,9 @@ pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
 
 #[derive(Eq, PartialEq, Clone)]
 enum Token {
-    ArrayStart(u8),
-    ArrayEnd(u8),
+    // Number of tokens up to the end of the array
+    ArrayStart(usize),
+    ArrayEnd,
     Value(u8),
 }
 
@@ -51,8 +81,8 @@ impl Signal {
             Token::ArrayStart(4),
             Token::ArrayStart(2),
             Token::Value(value),
-            Token::ArrayEnd(1),
-            Token::ArrayEnd(0),
+            Token::ArrayEnd,
+            Token::ArrayEnd,
         ];
 
         Signal(tokens)
@@ -71,59 +101,40 @@ impl PartialOrd for Signal {
     }
 }
 
-fn parse(input: &[&str]) -> Vec<Signal> {
-    input
-        .iter()
-        .filter(|line| !line.is_empty())
-        .map(|line| parse_signal(&mut line.bytes().peekable(), line.len()))
-        .collect::<Vec<_>>()
-}
-
-fn parse_signal(input: &mut Peekable<impl Iterator<Item = u8>>, size_hint: usize) -> Signal {
-    let mut tokens = Vec::with_capacity(size_hint);
-    let mut depth = 0;
-
-    while let Some(char) = input.next() {
-        match char {
-            b'[' => {
-                tokens.push(Token::ArrayStart(depth));
-                depth += 1;
-            }
-            b']' => {
-                depth -= 1;
-                tokens.push(Token::ArrayEnd(depth));
-            }
-            b',' => {}
-            _ if char.is_ascii_digit() => {
-                let mut value = char - b'0';
-
-                if matches!(input.peek(), Some(char) if char.is_ascii_digit()) {
-                    value = 10 * value + input.next().unwrap() - b'0';
-                }
-
-                tokens.push(Token::Value(value));
-            }
-            _ => unreachable!(),
+// Reads a packet such as [1,[2,3],[]], which is a single list
+fn parse_signal(index: usize, line: &str) -> Result<Signal, ParseError> {
+    let mut parser = LineParser::new(Day::Day13, index, line);
+    let mut tokens = Vec::with_capacity(line.len());
+    // Positions of the starts of the arrays which are still open
+    let mut open = vec![0];
+
+    parser.expect("[")?;
+    tokens.push(Token::ArrayStart(0));
+
+    while let Some(&start) = open.last() {
+        if parser.remaining().starts_with(']') {
+            parser.expect("]")?;
+            tokens[start] = Token::ArrayStart(tokens.len() - start);
+            tokens.push(Token::ArrayEnd);
+            open.pop();
+            continue;
         }
-    }
 
-    replace_depths_with_lengths(&mut tokens);
-
-    Signal(tokens)
-}
-
-fn replace_depths_with_lengths(tokens: &mut [Token]) {
-    for i in 0..tokens.len() {
-        let (head, tail) = tokens.split_at_mut(i + 1);
+        if !matches!(tokens.last(), Some(Token::ArrayStart(_))) {
+            parser.expect(",")?;
+        }
 
-        if let Token::ArrayStart(depth) = &mut head[i] {
-            *depth = tail
-                .iter()
-                .position(|token| matches!(token, Token::ArrayEnd(i) if i == depth))
-                .unwrap() as u8
-                + 1;
+        if parser.remaining().starts_with('[') {
+            parser.expect("[")?;
+            open.push(tokens.len());
+            tokens.push(Token::ArrayStart(0));
+        } else {
+            tokens.push(Token::Value(parser.number()?));
         }
     }
+
+    parser.end()?;
+    Ok(Signal(tokens))
 }
 
 fn compare(mut left: &[Token], mut right: &[Token]) -> Ordering {
@@ -150,11 +161,11 @@ fn compare(mut left: &[Token], mut right: &[Token]) -> Ordering {
     }
 }
 
-fn take_item(tokens: &[Token]) -> (Item, &[Token]) {
+fn take_item(tokens: &[Token]) -> (Item<'_>, &[Token]) {
     match &tokens[0] {
         Token::Value(value) => (Item::Value(*value), &tokens[1..]),
         Token::ArrayStart(length) => {
-            let length = *length as usize;
+            let length = *length;
             (Item::Array(&tokens[1..length]), &tokens[length + 1..])
         }
         _ => unreachable!(),
@@ -1,6 +1,13 @@
-use anyhow::Context;
+use super::{register, Answer, Day, LineParser, Parameters, ParseError};
 use std::ops::RangeInclusive;
-use std::str::FromStr;
+
+register! {
+    day: Day14,
+    title: "Regolith Reservoir",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
 
 // Assume we won't go out of these bounds for any input
 const MIN_X: usize = 300;
@@ -11,12 +18,17 @@ const MAX_Y: usize = 180;
 const WIDTH: usize = MAX_X - MIN_X + 1;
 const HEIGHT: usize = MAX_Y - MIN_Y + 1;
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(Cave::new(input)?.simulate_sand_without_floor())
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Cave> {
+    Ok(Cave::new(input)?)
+}
+
+// Both parts fill the cave with sand, so each of them starts from a copy of the rocks
+pub fn part_a(cave: &Cave) -> anyhow::Result<impl Into<Answer>> {
+    Ok(cave.clone().simulate_sand_without_floor())
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(Cave::new(input)?.simulate_sand_with_floor())
+pub fn part_b(cave: &Cave) -> anyhow::Result<impl Into<Answer>> {
+    Ok(cave.clone().simulate_sand_with_floor())
 }
 
 #[derive(Clone)]
@@ -30,41 +42,54 @@ impl Coordinate {
         x: (500 - MIN_X),
         y: 0,
     };
-}
-
-impl FromStr for Coordinate {
-    type Err = anyhow::Error;
 
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let (x, y) = input
-            .split_once(',')
-            .with_context(|| format!("Could not find , in {}", input))?;
-
-        let x = x.parse()?;
-        let y = y.parse()?;
+    // Reads a point such as 498,4, which has to be within the bounds. The floor is right below the
+    // lowest rock, so the lowest row is kept free for it.
+    fn parse(parser: &mut LineParser) -> Result<Self, ParseError> {
+        let position = parser.position();
+        let x = parser.number()?;
+        parser.expect(",")?;
+        let y = parser.number()?;
+
+        if !(MIN_X..=MAX_X).contains(&x) || !(MIN_Y..MAX_Y).contains(&y) {
+            let expected = format!(
+                "a point from {},{} to {},{}",
+                MIN_X,
+                MIN_Y,
+                MAX_X,
+                MAX_Y - 1
+            );
+
+            return Err(parser.error_at(position, expected));
+        }
 
         Ok(Coordinate { x, y })
     }
 }
 
-struct Cave {
+#[derive(Clone)]
+pub struct Cave {
     grid: Vec<Vec<bool>>,
     bottom: usize,
 }
 
 impl Cave {
-    fn new(input: &[&str]) -> anyhow::Result<Self> {
+    fn new(input: &[&str]) -> Result<Self, ParseError> {
         let mut bottom = 0;
         let mut grid = vec![vec![false; WIDTH]; HEIGHT];
 
-        for line in input {
-            let mut iterator = line.split_ascii_whitespace().step_by(2);
+        for (index, line) in input.iter().enumerate() {
+            let mut parser = LineParser::new(Day::Day14, index, line);
+            let mut start = Coordinate::parse(&mut parser)?;
 
-            let start = iterator.next().context("Unexpected end of input")?;
-            let mut start = Coordinate::from_str(start)?;
+            while !parser.is_empty() {
+                parser.expect(" -> ")?;
+                let position = parser.position();
+                let end = Coordinate::parse(&mut parser)?;
 
-            for end in iterator {
-                let end = Coordinate::from_str(end)?;
+                if start.x != end.x && start.y != end.y {
+                    return Err(parser.error_at(position, "a point in line with the previous one"));
+                }
 
                 if start.x != end.x {
                     let y = start.y - MIN_Y;
@@ -79,6 +104,8 @@ impl Cave {
                 } else {
                     let x = start.x - MIN_X;
 
+                    bottom = bottom.max(start.y - MIN_Y).max(end.y - MIN_Y);
+
                     for y in build_range(start.y - MIN_Y, end.y - MIN_Y) {
                         grid[y][x] = true;
                     }
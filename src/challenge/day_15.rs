@@ -1,22 +1,50 @@
-use anyhow::{bail, Context};
-use std::str::FromStr;
+use super::{register, Answer, Day, LineParser, Parameters, ParseError};
+use anyhow::bail;
+
+register! {
+    day: Day15,
+    title: "Beacon Exclusion Zone",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
+const ROW: isize = 2000000;
+const MAX: isize = 4000000;
+const FREQUENCY_MULTIPLIER: isize = 4000000;
+
+// Regions covered by the sensors, along with the row checked by the first part and the largest
+// coordinate of the beacon searched by the second one
+pub struct Sensors {
+    regions: Vec<Region>,
+    row: isize,
+    max: isize,
+}
+
+pub fn parse(input: &[&str], parameters: &Parameters) -> anyhow::Result<Sensors> {
+    Ok(Sensors {
+        regions: parse_regions(input)?,
+        row: parameters.get("row", ROW)?,
+        max: parameters.get("max", MAX)?,
+    })
+}
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    const Y: isize = 2000000;
+pub fn part_a(sensors: &Sensors) -> anyhow::Result<impl Into<Answer>> {
+    let row = sensors.row;
 
     let mut ranges = Vec::new();
     let mut beacons = Vec::new();
 
-    for region in parse_regions(input)? {
-        let range = match region.range_at_y(Y) {
+    for region in &sensors.regions {
+        let range = match region.range_at_y(row) {
             Some(range) => range,
             None => continue,
         };
 
         ranges.push(range);
 
-        if region.beacon_y == Y {
-            beacons.push(region.beacon_y);
+        if region.beacon_y == row {
+            beacons.push(region.beacon_x);
         }
     }
 
@@ -41,15 +69,14 @@ pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
     Ok(count as usize - beacons.len())
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    const MAX: isize = 4000000;
-
-    let regions = parse_regions(input)?;
+pub fn part_b(sensors: &Sensors) -> anyhow::Result<impl Into<Answer>> {
+    let max = sensors.max;
+    let regions = &sensors.regions;
 
     let mut lines_up = Vec::with_capacity(regions.len() * 2); // y = n + x
     let mut lines_down = Vec::with_capacity(regions.len() * 2); // y = n - x
 
-    for region in &regions {
+    for region in regions {
         let distance = region.distance() as isize + 1;
 
         lines_up.push(region.sensor_y - distance - region.sensor_x);
@@ -66,11 +93,11 @@ pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
             let x = (line_down - line_up) / 2;
             let y = x + line_up;
 
-            if (0..=MAX).contains(&x)
-                && (0..=MAX).contains(&y)
+            if (0..=max).contains(&x)
+                && (0..=max).contains(&y)
                 && !regions.iter().any(|region| region.contains(x, y))
             {
-                return Ok(x * MAX + y);
+                return Ok(x * FREQUENCY_MULTIPLIER + y);
             }
         }
     }
@@ -78,8 +105,12 @@ pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
     bail!("Could not find beacon");
 }
 
-fn parse_regions(input: &[&str]) -> anyhow::Result<Vec<Region>> {
-    input.iter().map(|line| line.parse()).collect()
+fn parse_regions(input: &[&str]) -> Result<Vec<Region>, ParseError> {
+    input
+        .iter()
+        .enumerate()
+        .map(|(index, line)| Region::parse(index, line))
+        .collect()
 }
 
 struct Range {
@@ -121,25 +152,21 @@ impl Region {
     }
 }
 
-impl FromStr for Region {
-    type Err = anyhow::Error;
-
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let (sensor_x, rest) = input[12..]
-            .split_once(',')
-            .with_context(|| format!("Could not find sensor x coordinate in: {}", input))?;
-        let (sensor_y, rest) = rest[3..]
-            .split_once(':')
-            .with_context(|| format!("Could not find sensor y coordinate in: {}", input))?;
-        let (beacon_x, rest) = rest[24..]
-            .split_once(',')
-            .with_context(|| format!("Could not find beacon x coordinate in: {}", input))?;
-        let beacon_y = &rest[3..];
-
-        let sensor_x = sensor_x.parse::<isize>()?;
-        let sensor_y = sensor_y.parse::<isize>()?;
-        let beacon_x = beacon_x.parse::<isize>()?;
-        let beacon_y = beacon_y.parse::<isize>()?;
+impl Region {
+    // Reads a line such as "Sensor at x=2, y=18: closest beacon is at x=-2, y=15". Coordinates fit
+    // in 32 bits, so that distances between them cannot overflow.
+    fn parse(index: usize, line: &str) -> Result<Self, ParseError> {
+        let mut parser = LineParser::new(Day::Day15, index, line);
+        let mut coordinate = |prefix: &str| {
+            parser.expect(prefix)?;
+            Ok::<_, ParseError>(parser.number::<i32>()? as isize)
+        };
+
+        let sensor_x = coordinate("Sensor at x=")?;
+        let sensor_y = coordinate(", y=")?;
+        let beacon_x = coordinate(": closest beacon is at x=")?;
+        let beacon_y = coordinate(", y=")?;
+        parser.end()?;
 
         Ok(Region {
             sensor_x,
@@ -1,45 +1,75 @@
+use super::{register, Answer, Cancellation, Day, LineParser, Parameters, ParseError};
+use anyhow::{bail, Context};
 use std::cmp::Reverse;
 use std::collections::HashMap;
 
+register! {
+    day: Day16,
+    title: "Proboscidea Volcanium",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
 const MAX_LINKS: usize = 5;
+// Valves with a flow rate have to fit in a ValveSet
+const MAX_VALVES: usize = 15;
+const START: &str = "AA";
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Graph> {
+    Graph::parse(input)
+}
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let graph = Graph::new(input);
-    let solutions = find_solutions(graph.valves.len(), 30, graph.closed_valves, &graph);
+pub fn part_a(graph: &Graph) -> anyhow::Result<impl Into<Answer>> {
+    let solutions = find_solutions(graph.valves.len(), 30, graph.closed_valves, graph)?;
 
+    // Waiting at the start is always a solution, so there is at least one
     let max_pressure = solutions
         .into_iter()
         .map(|solution| solution.total_pressure)
         .max()
-        .unwrap();
+        .unwrap_or_default();
 
     Ok(max_pressure)
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let graph = Graph::new(input);
+pub fn part_b(graph: &Graph) -> anyhow::Result<impl Into<Answer>> {
+    // Only the best solution for each set of opened valves matters
+    let mut best_solutions = HashMap::new();
 
-    let mut solutions = find_solutions(graph.valves.len(), 26, graph.closed_valves, &graph);
-    solutions.sort_by_key(|solution| Reverse(solution.total_pressure));
+    for solution in find_solutions(graph.valves.len(), 26, graph.closed_valves, graph)? {
+        let total_pressure = best_solutions.entry(solution.valves.0).or_insert(0);
+        *total_pressure = solution.total_pressure.max(*total_pressure);
+    }
 
-    let valves = solutions[0].valves;
-    let lower_bound = solutions[1..]
-        .iter()
-        .position(|solution| !solution.valves.overlaps(valves))
-        .unwrap();
+    let mut solutions = best_solutions
+        .into_iter()
+        .map(|(valves, total_pressure)| Solution {
+            valves: ValveSet::new(valves),
+            total_pressure,
+        })
+        .collect::<Vec<_>>();
 
-    let mut max_pressure = solutions[0].total_pressure + solutions[lower_bound].total_pressure;
+    solutions.sort_by_key(|solution| Reverse(solution.total_pressure));
 
-    for (index, player) in solutions[1..lower_bound - 1].iter().enumerate() {
-        for elephant in solutions[index + 1..lower_bound].iter() {
-            if player.valves.overlaps(elephant.valves) {
-                continue;
-            }
+    let mut max_pressure = 0;
+
+    for (index, player) in solutions.iter().enumerate() {
+        // The elephant can't do better than the player, since solutions are sorted
+        if player.total_pressure * 2 <= max_pressure {
+            break;
+        }
 
+        for elephant in &solutions[index + 1..] {
             let total_pressure = player.total_pressure + elephant.total_pressure;
 
-            if total_pressure > max_pressure {
+            if total_pressure <= max_pressure {
+                break;
+            }
+
+            if !player.valves.overlaps(elephant.valves) {
                 max_pressure = total_pressure;
+                break;
             }
         }
     }
@@ -52,7 +82,9 @@ fn find_solutions(
     remaining_time: usize,
     closed_valves: ValveSet,
     graphs: &Graph,
-) -> Vec<Solution> {
+) -> anyhow::Result<Vec<Solution>> {
+    let cancellation = Cancellation::current();
+
     let state = State {
         id,
         remaining_time,
@@ -64,15 +96,17 @@ fn find_solutions(
     let mut states = Vec::with_capacity(140000);
     states.push(state);
 
+    // Every state is a solution, since we can always stop opening valves and wait
     while let Some(state) = states.pop() {
-        let length = states.len();
+        cancellation.check()?;
 
         for (id, valve) in graphs.valves.iter().enumerate() {
             if !state.closed_valves.contains(id) {
                 continue;
             }
 
-            let effort = graphs.distance(state.id, id) + 1;
+            // Valves which cannot be reached are infinitely far away
+            let effort = graphs.distance(state.id, id).saturating_add(1);
 
             if effort > state.remaining_time {
                 continue;
@@ -92,17 +126,15 @@ fn find_solutions(
             states.push(state);
         }
 
-        if length == states.len() {
-            let solution = Solution {
-                valves: closed_valves.diff(state.closed_valves),
-                total_pressure: state.total_pressure,
-            };
+        let solution = Solution {
+            valves: closed_valves.diff(state.closed_valves),
+            total_pressure: state.total_pressure,
+        };
 
-            solutions.push(solution);
-        }
+        solutions.push(solution);
     }
 
-    solutions
+    Ok(solutions)
 }
 
 struct Solution {
@@ -117,26 +149,38 @@ struct State {
     total_pressure: usize,
 }
 
-struct Graph {
+// Valves with a flow rate, along with the distances between them and from the start
+pub struct Graph {
     valves: Vec<Valve>,
     distances: Vec<usize>,
     closed_valves: ValveSet,
 }
 
 impl Graph {
-    fn new(input: &[&str]) -> Self {
+    fn parse(input: &[&str]) -> anyhow::Result<Self> {
+        let lines = input
+            .iter()
+            .enumerate()
+            .map(|(index, line)| ValveLine::parse(index, line))
+            .collect::<Result<Vec<_>, _>>()?;
+
         let mut ids = HashMap::new();
 
-        for line in input {
-            ids.insert(&line[6..8], ids.len());
+        for line in &lines {
+            if ids.insert(line.name.name, ids.len()).is_some() {
+                return Err(line
+                    .name
+                    .error(input, "a valve which is not listed yet")
+                    .into());
+            }
         }
 
-        let start = *ids.get("AA").unwrap();
+        let start = *ids.get(START).context("Valve AA is missing")?;
 
-        let all_valves = input
+        let all_valves = lines
             .iter()
-            .map(|line| Valve::new(line, &ids))
-            .collect::<Vec<_>>();
+            .map(|line| Valve::new(line, &ids, input))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let all_distances = compute_distances(&all_valves);
 
@@ -152,10 +196,19 @@ impl Graph {
             }
         }
 
+        if valves.len() > MAX_VALVES + 1 {
+            bail!(
+                "Only up to {} valves can have a flow rate, found {}",
+                MAX_VALVES,
+                valves.len() - 1
+            );
+        }
+
+        // The starting valve is always kept
         let start = original_valve_lookup
             .iter()
             .position(|id| *id == start)
-            .unwrap();
+            .unwrap_or_default();
         let last_index = valves.len() - 1;
         valves.swap(start, last_index);
         original_valve_lookup.swap(start, last_index);
@@ -173,11 +226,11 @@ impl Graph {
 
         let closed_valves = ValveSet::new((1 << valves.len()) - 1);
 
-        Graph {
+        Ok(Graph {
             valves,
             distances,
             closed_valves,
-        }
+        })
     }
 
     fn distance(&self, source: usize, destination: usize) -> usize {
@@ -223,24 +276,98 @@ struct Valve {
     link_count: usize,
 }
 
-impl Valve {
-    fn new(input: &str, ids: &HashMap<&str, usize>) -> Self {
-        let (flow_rate, input) = input[23..].split_once(';').unwrap();
-        let flow_rate = flow_rate.parse().unwrap();
+// A line such as "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB", before the names
+// are turned into ids
+struct ValveLine<'a> {
+    name: ValveName<'a>,
+    flow_rate: usize,
+    links: Vec<ValveName<'a>>,
+}
 
-        let mut links = [0; MAX_LINKS];
-        let mut link_count = 0;
+// Name of a valve, along with where it appears in the input for errors
+struct ValveName<'a> {
+    name: &'a str,
+    line: usize,
+    position: usize,
+}
+
+impl<'a> ValveLine<'a> {
+    fn parse(index: usize, line: &'a str) -> Result<Self, ParseError> {
+        let mut parser = LineParser::new(Day::Day16, index, line);
+
+        parser.expect("Valve ")?;
+        let name = ValveName::parse(&mut parser, index)?;
+        parser.expect(" has flow rate=")?;
+        let flow_rate = parser.number()?;
 
-        for link in input[23..].trim_start().split(", ") {
-            links[link_count] = *ids.get(link).unwrap();
-            link_count += 1;
+        if parser.remaining().starts_with("; tunnel leads") {
+            parser.expect("; tunnel leads to valve ")?;
+        } else {
+            parser.expect("; tunnels lead to valves ")?;
         }
 
-        Valve {
+        let mut links = vec![ValveName::parse(&mut parser, index)?];
+
+        while !parser.is_empty() {
+            parser.expect(", ")?;
+            links.push(ValveName::parse(&mut parser, index)?);
+        }
+
+        Ok(ValveLine {
+            name,
             flow_rate,
             links,
-            link_count,
+        })
+    }
+}
+
+impl<'a> ValveName<'a> {
+    // Names are two capital letters
+    fn parse(parser: &mut LineParser<'a>, line: usize) -> Result<Self, ParseError> {
+        let position = parser.position();
+        let name = parser
+            .remaining()
+            .get(..2)
+            .filter(|name| name.bytes().all(|byte| byte.is_ascii_uppercase()))
+            .ok_or_else(|| parser.error("the name of a valve"))?;
+
+        parser.expect(name)?;
+
+        Ok(ValveName {
+            name,
+            line,
+            position,
+        })
+    }
+
+    fn error(&self, input: &[&str], expected: &str) -> ParseError {
+        LineParser::new(Day::Day16, self.line, input[self.line]).error_at(self.position, expected)
+    }
+}
+
+impl Valve {
+    fn new(
+        line: &ValveLine,
+        ids: &HashMap<&str, usize>,
+        input: &[&str],
+    ) -> Result<Self, ParseError> {
+        let mut links = [0; MAX_LINKS];
+
+        if let Some(link) = line.links.get(MAX_LINKS) {
+            return Err(link.error(input, "the end of the line"));
+        }
+
+        for (id, link) in links.iter_mut().zip(&line.links) {
+            *id = *ids
+                .get(link.name)
+                .ok_or_else(|| link.error(input, "the name of a listed valve"))?;
         }
+
+        Ok(Valve {
+            flow_rate: line.flow_rate,
+            links,
+            link_count: line.links.len(),
+        })
     }
 
     fn links(&self) -> &[usize] {
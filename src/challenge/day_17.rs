@@ -1,3 +1,14 @@
+use super::{register, Answer, Day, LineParser, Parameters, ParseError};
+use anyhow::Context;
+
+register! {
+    day: Day17,
+    title: "Pyroclastic Flow",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
 const SHAPES: [Shape; 5] = [
     Shape([0b11110, 0, 0, 0]),
     Shape([0b1000, 0b11100, 0b1000, 0]),
@@ -13,8 +24,14 @@ const PATTERN_LOOKUP_HEIGHT: usize = 10;
 // Assume the pattern will after this many rows
 const PATTERN_LOOKUP_OFFSET: usize = 10;
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let mut chamber = Chamber::new(input[0]);
+pub struct Jets(Vec<Direction>);
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Jets> {
+    Ok(Jets(parse_jets(input)?))
+}
+
+pub fn part_a(jets: &Jets) -> anyhow::Result<impl Into<Answer>> {
+    let mut chamber = Chamber::new(&jets.0);
 
     for _ in 0..2022 {
         chamber.drop();
@@ -23,8 +40,8 @@ pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
     Ok(chamber.height())
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let mut chamber = Chamber::new(input[0]);
+pub fn part_b(jets: &Jets) -> anyhow::Result<impl Into<Answer>> {
+    let mut chamber = Chamber::new(&jets.0);
 
     for _ in 0..SCAN_SHAPE_COUNT {
         chamber.drop();
@@ -35,9 +52,14 @@ pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
     let pattern_lookup_end = pattern_start - PATTERN_LOOKUP_HEIGHT;
     let search = &chamber.rocks[pattern_lookup_end..pattern_start];
 
-    let pattern_height = (search.len()..)
+    let pattern_height = (search.len()..=pattern_lookup_end)
         .find(|i| search == &chamber.rocks[pattern_start - search.len() - i..pattern_start - i])
-        .unwrap();
+        .with_context(|| {
+            format!(
+                "The rocks do not form a repeating pattern within {} shapes",
+                SCAN_SHAPE_COUNT
+            )
+        })?;
 
     let next_pattern_start = pattern_start + pattern_height;
     let next_pattern_lookup_end = next_pattern_start - PATTERN_LOOKUP_HEIGHT;
@@ -74,6 +96,33 @@ pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
     Ok(chamber.height() + remaining / pattern_shape_count * pattern_height)
 }
 
+// The jet pattern is a single line of < and >
+fn parse_jets(input: &[&str]) -> Result<Vec<Direction>, ParseError> {
+    let line = match input {
+        [line] => line,
+        [] => return Err(LineParser::new(Day::Day17, 0, "").error("a jet pattern")),
+        [_, extra, ..] => {
+            return Err(LineParser::new(Day::Day17, 1, extra).error("the end of the input"))
+        }
+    };
+
+    let mut parser = LineParser::new(Day::Day17, 0, line);
+    let mut shifts = Vec::with_capacity(line.len());
+
+    while !parser.is_empty() {
+        shifts.push(match parser.one_of(&['<', '>'])? {
+            '<' => Direction::Left,
+            _ => Direction::Right,
+        });
+    }
+
+    if shifts.is_empty() {
+        return Err(parser.error("a jet pattern"));
+    }
+
+    Ok(shifts)
+}
+
 struct Shapes(usize);
 
 impl Shapes {
@@ -84,18 +133,13 @@ impl Shapes {
     }
 }
 
-struct Shifts<'a>(usize, &'a [u8]);
+struct Shifts<'a>(usize, &'a [Direction]);
 
 impl Shifts<'_> {
     fn next(&mut self) -> Direction {
         let index = self.0;
         self.0 = (self.0 + 1) % self.1.len();
-
-        match self.1[index] {
-            b'<' => Direction::Left,
-            b'>' => Direction::Right,
-            _ => unreachable!(),
-        }
+        self.1[index]
     }
 }
 
@@ -137,12 +181,12 @@ struct Chamber<'a> {
 }
 
 impl<'a> Chamber<'a> {
-    fn new(shifts: &'a str) -> Self {
+    fn new(shifts: &'a [Direction]) -> Self {
         Chamber {
             shape_count: 0,
             rocks: vec![0b1111111; 4],
             shapes: Shapes(0),
-            shifts: Shifts(0, shifts.as_bytes()),
+            shifts: Shifts(0, shifts),
         }
     }
 
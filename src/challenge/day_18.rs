@@ -1,12 +1,23 @@
-use anyhow::Context;
-use std::str::FromStr;
+use super::{register, Answer, Day, LineParser, Parameters, ParseError};
+
+register! {
+    day: Day18,
+    title: "Boiling Boulders",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
 
 const GRID_SIZE: usize = 22;
+// Cubes are shifted by one, leaving room for water on every side of the droplet
+const MAX_POSITION: u8 = GRID_SIZE as u8 - 3;
 const CUBE_SIDES: usize = 6;
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let grid = build_grid(input)?;
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Grid> {
+    Ok(build_grid(input)?)
+}
 
+pub fn part_a(grid: &Grid) -> anyhow::Result<impl Into<Answer>> {
     let surface = Coordinate::all()
         .filter(|coordinate| grid.get(*coordinate) == Block::Lava)
         .map(|coordinate| {
@@ -23,31 +34,41 @@ pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
     Ok(surface)
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let mut grid = build_grid(input)?;
+// Water fills the grid from the outside, so it starts from a copy of the droplet
+pub fn part_b(grid: &Grid) -> anyhow::Result<impl Into<Answer>> {
+    let mut grid = grid.clone();
     Ok(calculate_surface(Coordinate::new(0, 0, 0), &mut grid))
 }
 
-fn calculate_surface(coordinate: Coordinate, grid: &mut Grid) -> usize {
-    if !grid.replace(coordinate, Block::Air, Block::Water) {
-        return 0;
+fn calculate_surface(start: Coordinate, grid: &mut Grid) -> usize {
+    let mut surface = 0;
+    let mut stack = vec![start];
+
+    while let Some(coordinate) = stack.pop() {
+        if !grid.replace(coordinate, Block::Air, Block::Water) {
+            continue;
+        }
+
+        for neighbor in coordinate.neighbors() {
+            match grid.get(neighbor) {
+                Block::Air => stack.push(neighbor),
+                Block::Lava => surface += 1,
+                Block::Water => {}
+            }
+        }
     }
 
-    coordinate
-        .neighbors()
-        .map(|neighbor| match grid.get(neighbor) {
-            Block::Air => calculate_surface(neighbor, grid),
-            Block::Lava => 1,
-            Block::Water => 0,
-        })
-        .sum()
+    surface
 }
 
-fn build_grid(input: &[&str]) -> anyhow::Result<Grid> {
-    input.iter().try_fold(Grid::new(), |mut grid, line| {
-        grid.set(line.parse()?, Block::Lava);
-        Ok(grid)
-    })
+fn build_grid(input: &[&str]) -> Result<Grid, ParseError> {
+    input
+        .iter()
+        .enumerate()
+        .try_fold(Grid::new(), |mut grid, (index, line)| {
+            grid.set(Coordinate::parse(index, line)?, Block::Lava);
+            Ok(grid)
+        })
 }
 
 #[derive(Copy, Clone)]
@@ -58,6 +79,28 @@ struct Coordinate {
 }
 
 impl Coordinate {
+    // Reads a line such as "2,2,2"
+    fn parse(index: usize, line: &str) -> Result<Self, ParseError> {
+        let mut parser = LineParser::new(Day::Day18, index, line);
+        let position = |parser: &mut LineParser| {
+            let start = parser.position();
+
+            match parser.number::<u8>() {
+                Ok(position) if position <= MAX_POSITION => Ok(position + 1),
+                _ => Err(parser.error_at(start, format!("a number from 0 to {}", MAX_POSITION))),
+            }
+        };
+
+        let x = position(&mut parser)?;
+        parser.expect(",")?;
+        let y = position(&mut parser)?;
+        parser.expect(",")?;
+        let z = position(&mut parser)?;
+        parser.end()?;
+
+        Ok(Coordinate::new(x, y, z))
+    }
+
     fn new(x: u8, y: u8, z: u8) -> Self {
         Coordinate { x, y, z }
     }
@@ -113,29 +156,6 @@ impl Coordinate {
     }
 }
 
-impl FromStr for Coordinate {
-    type Err = anyhow::Error;
-
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let mut iterator = input.split(',');
-
-        let x: u8 = iterator
-            .next()
-            .context("Unexpected end of input")?
-            .parse()?;
-        let y: u8 = iterator
-            .next()
-            .context("Unexpected end of input")?
-            .parse()?;
-        let z: u8 = iterator
-            .next()
-            .context("Unexpected end of input")?
-            .parse()?;
-
-        Ok(Coordinate::new(x + 1, y + 1, z + 1))
-    }
-}
-
 #[derive(Eq, PartialEq, Copy, Clone)]
 enum Block {
     Air,
@@ -143,7 +163,8 @@ enum Block {
     Water,
 }
 
-struct Grid(Vec<Block>);
+#[derive(Clone)]
+pub struct Grid(Vec<Block>);
 
 impl Grid {
     fn new() -> Self {
@@ -1,18 +1,48 @@
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(input
-        .iter()
-        .map(|line| {
-            let blueprint = Blueprint::new(line);
-            blueprint.id * score_blueprint(&blueprint, 24)
-        })
-        .sum::<usize>())
+use super::{register, Answer, Cancellation, Day, LineParser, Parameters, ParseError};
+
+register! {
+    day: Day19,
+    title: "Not Enough Minerals",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(input[..3]
+// Only states with at least this many minutes left check for cancellation, which keeps the check
+// out of the leaves where the search spends most of its time
+const CANCELLATION_MINUTE: u8 = 12;
+
+// Blueprints are parsed once and shared by both parts
+pub struct Blueprints(Vec<Blueprint>);
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Blueprints> {
+    let blueprints = input
         .iter()
-        .map(|line| score_blueprint(&Blueprint::new(line), 32))
-        .product::<usize>())
+        .enumerate()
+        .map(|(index, line)| Blueprint::parse(index, line))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Blueprints(blueprints))
+}
+
+pub fn part_a(blueprints: &Blueprints) -> anyhow::Result<impl Into<Answer>> {
+    let mut result = 0;
+
+    for blueprint in &blueprints.0 {
+        result += blueprint.id * score_blueprint(blueprint, 24)?;
+    }
+
+    Ok(result)
+}
+
+pub fn part_b(blueprints: &Blueprints) -> anyhow::Result<impl Into<Answer>> {
+    let mut result = 1;
+
+    for blueprint in blueprints.0.iter().take(3) {
+        result *= score_blueprint(blueprint, 32)?;
+    }
+
+    Ok(result)
 }
 
 #[derive(Default, Clone)]
@@ -93,23 +123,34 @@ struct Blueprint {
 }
 
 impl Blueprint {
-    fn new(blueprint: &str) -> Self {
-        let (id, blueprint) = blueprint[10..].split_once(':').unwrap();
-        let (ore_robot_ore, blueprint) = blueprint[22..].split_once(' ').unwrap();
-        let (clay_robot_ore, blueprint) = blueprint[27..].split_once(' ').unwrap();
-        let (obsidian_robot_ore, blueprint) = blueprint[31..].split_once(' ').unwrap();
-        let (obsidian_robot_clay, blueprint) = blueprint[8..].split_once(' ').unwrap();
-        let (geode_robot_ore, blueprint) = blueprint[29..].split_once(' ').unwrap();
-        let (geode_robot_obsidian, _) = blueprint[8..].split_once(' ').unwrap();
+    fn parse(index: usize, line: &str) -> Result<Self, ParseError> {
+        let mut parser = LineParser::new(Day::Day19, index, line);
+
+        parser.expect("Blueprint ")?;
+        let id = parser.number()?;
+        parser.expect(": Each ore robot costs ")?;
+        let ore_robot_ore = parser.number()?;
+        parser.expect(" ore. Each clay robot costs ")?;
+        let clay_robot_ore = parser.number()?;
+        parser.expect(" ore. Each obsidian robot costs ")?;
+        let obsidian_robot_ore = parser.number()?;
+        parser.expect(" ore and ")?;
+        let obsidian_robot_clay = parser.number()?;
+        parser.expect(" clay. Each geode robot costs ")?;
+        let geode_robot_ore = parser.number()?;
+        parser.expect(" ore and ")?;
+        let geode_robot_obsidian = parser.number()?;
+        parser.expect(" obsidian.")?;
+        parser.end()?;
 
         let mut blueprint = Blueprint {
-            id: id.parse().unwrap(),
-            ore_robot_ore: ore_robot_ore.parse().unwrap(),
-            clay_robot_ore: clay_robot_ore.parse().unwrap(),
-            obsidian_robot_ore: obsidian_robot_ore.parse().unwrap(),
-            obsidian_robot_clay: obsidian_robot_clay.parse().unwrap(),
-            geode_robot_ore: geode_robot_ore.parse().unwrap(),
-            geode_robot_obsidian: geode_robot_obsidian.parse().unwrap(),
+            id,
+            ore_robot_ore,
+            clay_robot_ore,
+            obsidian_robot_ore,
+            obsidian_robot_clay,
+            geode_robot_ore,
+            geode_robot_obsidian,
             max_ore_cost: 0,
         };
 
@@ -119,11 +160,11 @@ impl Blueprint {
             .max(blueprint.obsidian_robot_ore)
             .max(blueprint.geode_robot_ore);
 
-        blueprint
+        Ok(blueprint)
     }
 }
 
-fn score_blueprint(blueprint: &Blueprint, time: u8) -> usize {
+fn score_blueprint(blueprint: &Blueprint, time: u8) -> anyhow::Result<usize> {
     let mut state = State {
         minute: time,
         ..Default::default()
@@ -131,7 +172,10 @@ fn score_blueprint(blueprint: &Blueprint, time: u8) -> usize {
 
     state.ore.production = 1;
 
-    simulate(blueprint, state, 0) as usize
+    let score = simulate(blueprint, state, 0) as usize;
+    // A cancelled search gives up early, so its score means nothing
+    Cancellation::current().check()?;
+    Ok(score)
 }
 
 fn simulate(blueprint: &Blueprint, mut state: State, mut min_score: u8) -> u8 {
@@ -139,6 +183,10 @@ fn simulate(blueprint: &Blueprint, mut state: State, mut min_score: u8) -> u8 {
         return state.geode.count;
     }
 
+    if state.minute >= CANCELLATION_MINUTE && Cancellation::current().is_cancelled() {
+        return 0;
+    }
+
     let can_build_geode_robot = can_build_geode_robot(blueprint, &state);
     let can_build_obsidian_robot = can_build_obsidian_robot(blueprint, &state);
     let can_build_clay_robot = can_build_clay_robot(blueprint, &state);
@@ -188,13 +236,13 @@ fn simulate(blueprint: &Blueprint, mut state: State, mut min_score: u8) -> u8 {
     max_score.max(score)
 }
 
+// Upper bound assuming a geode robot gets built every remaining minute, starting with this one
 fn compute_max_potential(state: &State) -> usize {
     let minute = state.minute as usize;
     let count = state.geode.count as usize;
     let production = state.geode.production as usize;
-    let minute_minus_one = minute.saturating_sub(1);
 
-    count + (minute * minute_minus_one / 2) + (production * (minute_minus_one + 1))
+    count + (minute * (minute + 1) / 2) + (production * minute)
 }
 
 fn simulate_build_ore_robot(blueprint: &Blueprint, mut state: State, min_score: u8) -> u8 {
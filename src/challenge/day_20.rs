@@ -1,11 +1,41 @@
+use super::{register, Answer, Day, LineParser, Parameters};
+use anyhow::{bail, Context};
 use std::cmp::Ordering;
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(CircularList::new(input, 1).decrypt(1))
+register! {
+    day: Day20,
+    title: "Grove Positioning System",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(CircularList::new(input, 811589153).decrypt(10))
+// Numbers of the encrypted file, which are moved around the other numbers, so there have to be
+// some
+pub struct File(Vec<i64>);
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<File> {
+    let mut numbers = Vec::with_capacity(input.len());
+
+    for (i, line) in input.iter().enumerate() {
+        let mut parser = LineParser::new(Day::Day20, i, line);
+        numbers.push(parser.number::<i64>()?);
+        parser.end()?;
+    }
+
+    if numbers.len() < 2 {
+        bail!("Expected at least 2 numbers, found {}", numbers.len());
+    }
+
+    Ok(File(numbers))
+}
+
+pub fn part_a(file: &File) -> anyhow::Result<impl Into<Answer>> {
+    CircularList::new(&file.0, 1)?.decrypt(1)
+}
+
+pub fn part_b(file: &File) -> anyhow::Result<impl Into<Answer>> {
+    CircularList::new(&file.0, 811589153)?.decrypt(10)
 }
 
 struct Node {
@@ -17,16 +47,24 @@ struct Node {
 struct CircularList(Vec<Node>);
 
 impl CircularList {
-    fn new(input: &[&str], decryption_key: i64) -> Self {
-        let mut nodes = input
-            .iter()
-            .enumerate()
-            .map(|(i, value)| Node {
-                value: value.parse::<i64>().unwrap() * decryption_key,
+    fn new(numbers: &[i64], decryption_key: i64) -> anyhow::Result<Self> {
+        let mut nodes = Vec::with_capacity(numbers.len());
+
+        for (i, &number) in numbers.iter().enumerate() {
+            let value = number.checked_mul(decryption_key).with_context(|| {
+                format!(
+                    "Number {} is too large to decrypt on line {}",
+                    number,
+                    i + 1
+                )
+            })?;
+
+            nodes.push(Node {
+                value,
                 next: i + 1,
                 previous: i.saturating_sub(1),
-            })
-            .collect::<Vec<_>>();
+            });
+        }
 
         let length = nodes.len();
 
@@ -38,10 +76,10 @@ impl CircularList {
             last.next = 0;
         }
 
-        CircularList(nodes)
+        Ok(CircularList(nodes))
     }
 
-    fn decrypt(&mut self, iterations: usize) -> i64 {
+    fn decrypt(&mut self, iterations: usize) -> anyhow::Result<i64> {
         // When we move around numbers during decryption, the number being moved is not in the list
         let length = self.0.len() as i64 - 1;
 
@@ -65,13 +103,19 @@ impl CircularList {
             }
         }
 
-        let index = self.0.iter().position(|node| node.value == 0).unwrap();
+        let index = self
+            .0
+            .iter()
+            .position(|node| node.value == 0)
+            .context("None of the numbers is 0")?;
         let length = self.0.len() as i64;
 
         [1000, 2000, 3000]
             .iter()
-            .map(|i| self.0[self.find_next_node(index, *i, length)].value)
-            .sum()
+            .try_fold(0i64, |sum, i| {
+                sum.checked_add(self.0[self.find_next_node(index, *i, length)].value)
+            })
+            .context("The grove coordinates are too large to add up")
     }
 
     fn find_next_node(&self, start: usize, count: i64, length: i64) -> usize {
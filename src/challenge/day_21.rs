@@ -1,102 +1,301 @@
+use super::{register, Answer, Day, LineParser, ParseError};
+use anyhow::{bail, Context};
 use std::collections::HashMap;
+use std::fmt::Display;
+
+register! {
+    day: Day21,
+    title: "Monkey Math",
+    part_a: lines(part_a),
+    part_b: lines(part_b),
+}
 
 const ROOT: &str = "root";
 const HUMAN: &str = "humn";
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(evaluate(ROOT, false, &parse_monkeys(input)).unwrap())
+pub fn part_a(input: &[&str]) -> anyhow::Result<impl Into<Answer>> {
+    let monkeys = Monkeys::parse(input)?;
+    let values = monkeys.evaluate(false)?;
+
+    values
+        .get(ROOT)
+        .copied()
+        .flatten()
+        .context("The root monkey is missing")
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let monkeys = parse_monkeys(input);
+pub fn part_b(input: &[&str]) -> anyhow::Result<impl Into<Answer>> {
+    let monkeys = Monkeys::parse(input)?;
 
-    let mut iterator = monkeys[ROOT].split_ascii_whitespace();
-    let left = iterator.next().unwrap();
-    let right = iterator.nth(1).unwrap();
+    let (left, right) = match monkeys.jobs.get(ROOT) {
+        Some(Job::Operation(_, [left, right])) => (left.name, right.name),
+        Some(Job::Number(_)) => bail!("The root monkey does not compare two monkeys"),
+        None => bail!("The root monkey is missing"),
+    };
 
-    let left_result = evaluate(left, true, &monkeys);
-    let right_result = evaluate(right, true, &monkeys);
+    let values = monkeys.evaluate(true)?;
 
-    let solution = match (left_result, right_result) {
-        (Some(result), _) => solve(right, result, &monkeys),
-        (_, Some(result)) => solve(left, result, &monkeys),
-        _ => unreachable!(),
-    };
+    match (values[left], values[right]) {
+        (Some(result), None) => monkeys.solve(right, result, &values),
+        (None, Some(result)) => monkeys.solve(left, result, &values),
+        (Some(_), Some(_)) => bail!("Neither side of the root monkey depends on the human"),
+        (None, None) => bail!("Both sides of the root monkey depend on the human"),
+    }
+}
+
+struct Monkeys<'a> {
+    jobs: HashMap<&'a str, Job<'a>>,
+    // Every monkey comes after the ones it waits for
+    order: Vec<&'a str>,
+}
 
-    Ok(solution)
+enum Job<'a> {
+    Number(u64),
+    Operation(Operator, [Operand<'a>; 2]),
 }
 
-fn parse_monkeys<'a>(input: &[&'a str]) -> HashMap<&'a str, &'a str> {
-    input
-        .iter()
-        .map(|line| (&line[..4], &line[6..]))
-        .collect::<HashMap<_, _>>()
+// Name of a monkey waited for, along with where it appears in the input for errors
+struct Operand<'a> {
+    name: &'a str,
+    line: usize,
+    position: usize,
 }
 
-fn evaluate(name: &str, human: bool, monkeys: &HashMap<&str, &str>) -> Option<u64> {
-    if human && name == HUMAN {
-        return None;
+#[derive(Copy, Clone)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl<'a> Monkeys<'a> {
+    // Checks that every monkey waited for exists and that no monkey ends up waiting for itself, so
+    // that the monkeys can be evaluated in order
+    fn parse(input: &[&'a str]) -> Result<Self, ParseError> {
+        let mut jobs = HashMap::with_capacity(input.len());
+        let mut names = Vec::with_capacity(input.len());
+
+        for (index, line) in input.iter().enumerate() {
+            let mut parser = LineParser::new(Day::Day21, index, line);
+            let name = parser.until(':')?;
+            parser.expect(": ")?;
+
+            let job = if parser
+                .remaining()
+                .starts_with(|char: char| char.is_ascii_digit())
+            {
+                Job::Number(parser.number()?)
+            } else {
+                let operand = |parser: &mut LineParser<'a>| {
+                    let position = parser.position();
+                    let name = parser.word()?;
+
+                    Ok::<_, ParseError>(Operand {
+                        name,
+                        line: index,
+                        position,
+                    })
+                };
+
+                let left = operand(&mut parser)?;
+                parser.expect(" ")?;
+
+                let operator = match parser.one_of(&['+', '-', '*', '/'])? {
+                    '+' => Operator::Add,
+                    '-' => Operator::Subtract,
+                    '*' => Operator::Multiply,
+                    _ => Operator::Divide,
+                };
+
+                parser.expect(" ")?;
+                let right = operand(&mut parser)?;
+                Job::Operation(operator, [left, right])
+            };
+
+            parser.end()?;
+
+            if jobs.insert(name, job).is_none() {
+                names.push(name);
+            }
+        }
+
+        let error = |operand: &Operand, expected: &str| {
+            LineParser::new(Day::Day21, operand.line, input[operand.line])
+                .error_at(operand.position, expected)
+        };
+
+        for job in jobs.values() {
+            for operand in job.operands() {
+                if !jobs.contains_key(operand.name) {
+                    return Err(error(operand, "the name of a monkey"));
+                }
+            }
+        }
+
+        // Depth-first search without recursion, a monkey still being visited is part of a cycle
+        let mut done = HashMap::<&str, bool>::with_capacity(jobs.len());
+        let mut order = Vec::with_capacity(jobs.len());
+
+        for name in names {
+            if done.contains_key(name) {
+                continue;
+            }
+
+            let mut stack = vec![(name, 0)];
+            done.insert(name, false);
+
+            while let Some(&(name, next)) = stack.last() {
+                match jobs[name].operands().get(next) {
+                    Some(operand) => {
+                        stack.last_mut().unwrap().1 += 1;
+
+                        match done.get(operand.name) {
+                            Some(true) => {}
+                            Some(false) => {
+                                return Err(error(
+                                    operand,
+                                    "a monkey which does not wait for itself",
+                                ))
+                            }
+                            None => {
+                                done.insert(operand.name, false);
+                                stack.push((operand.name, 0));
+                            }
+                        }
+                    }
+                    None => {
+                        done.insert(name, true);
+                        order.push(name);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        Ok(Monkeys { jobs, order })
     }
 
-    let value = monkeys[name];
+    // Value of every monkey, which is None for the human and every monkey waiting for it when the
+    // human is unknown
+    fn evaluate(&self, human: bool) -> anyhow::Result<HashMap<&'a str, Option<u64>>> {
+        let mut values = HashMap::with_capacity(self.order.len());
 
-    if value.as_bytes()[0].is_ascii_digit() {
-        return Some(value.parse().unwrap());
+        for &name in &self.order {
+            let value = match &self.jobs[name] {
+                _ if human && name == HUMAN => None,
+                Job::Number(value) => Some(*value),
+                Job::Operation(operator, [left, right]) => {
+                    match (values[left.name], values[right.name]) {
+                        (Some(left), Some(right)) => {
+                            Some(operator.apply(left, right).with_context(|| {
+                                format!(
+                                    "Monkey {} cannot compute {} {} {}",
+                                    name, left, operator, right
+                                )
+                            })?)
+                        }
+                        _ => None,
+                    }
+                }
+            };
+
+            values.insert(name, value);
+        }
+
+        Ok(values)
     }
 
-    let mut iterator = value.split_ascii_whitespace();
+    // Works back from the result of the monkey to the number the human has to yell, undoing one
+    // operation at a time
+    fn solve(
+        &self,
+        mut name: &'a str,
+        mut result: u64,
+        values: &HashMap<&'a str, Option<u64>>,
+    ) -> anyhow::Result<u64> {
+        while name != HUMAN {
+            let (left, operator, right) = match &self.jobs[name] {
+                Job::Operation(operator, [left, right]) => (left.name, *operator, right.name),
+                Job::Number(_) => bail!("Monkey {} does not wait for the human", name),
+            };
 
-    let left = evaluate(iterator.next().unwrap(), human, monkeys)?;
-    let operator = iterator.next().unwrap().as_bytes()[0];
-    let right = evaluate(iterator.next().unwrap(), human, monkeys)?;
+            let (next, solved) = match (values[left], values[right]) {
+                (Some(value), None) => (right, operator.solve_right(value, result)),
+                (None, Some(value)) => (left, operator.solve_left(value, result)),
+                _ => bail!("Both monkeys {} waits for depend on the human", name),
+            };
 
-    let result = match operator {
-        b'+' => left + right,
-        b'-' => left - right,
-        b'*' => left * right,
-        b'/' => left / right,
-        _ => unreachable!(),
-    };
+            result = solved.with_context(|| {
+                format!(
+                    "Monkey {} cannot yell {} whatever the human yells",
+                    name, result
+                )
+            })?;
+            name = next;
+        }
 
-    Some(result)
+        Ok(result)
+    }
 }
 
-fn solve(name: &str, result: u64, monkeys: &HashMap<&str, &str>) -> u64 {
-    if name == HUMAN {
-        return result;
+impl<'a> Job<'a> {
+    fn operands(&self) -> &[Operand<'a>] {
+        match self {
+            Job::Number(_) => &[],
+            Job::Operation(_, operands) => operands,
+        }
     }
+}
 
-    let mut iterator = monkeys[name].split_ascii_whitespace();
+impl Operator {
+    // None when the result does not fit or when dividing by zero
+    fn apply(self, left: u64, right: u64) -> Option<u64> {
+        match self {
+            Operator::Add => left.checked_add(right),
+            Operator::Subtract => left.checked_sub(right),
+            Operator::Multiply => left.checked_mul(right),
+            Operator::Divide => left.checked_div(right),
+        }
+    }
 
-    let left = iterator.next().unwrap();
-    let operator = iterator.next().unwrap().as_bytes()[0];
-    let right = iterator.next().unwrap();
+    // Finds left such that left (operator) value = result
+    fn solve_left(self, value: u64, result: u64) -> Option<u64> {
+        match self {
+            Operator::Add => result.checked_sub(value),
+            Operator::Subtract => result.checked_add(value),
+            Operator::Multiply => divide_exactly(result, value),
+            Operator::Divide => result.checked_mul(value),
+        }
+    }
 
-    if let Some(value) = evaluate(left, true, monkeys) {
-        solve(right, solve_right(value, result, operator), monkeys)
-    } else if let Some(value) = evaluate(right, true, monkeys) {
-        solve(left, solve_left(value, result, operator), monkeys)
-    } else {
-        unreachable!()
+    // Finds right such that value (operator) right = result
+    fn solve_right(self, value: u64, result: u64) -> Option<u64> {
+        match self {
+            Operator::Add => result.checked_sub(value),
+            Operator::Subtract => value.checked_sub(result),
+            Operator::Multiply => divide_exactly(result, value),
+            Operator::Divide => value.checked_div(result),
+        }
     }
 }
 
-fn solve_left(value: u64, result: u64, operator: u8) -> u64 {
-    match operator {
-        b'+' => result - value,
-        b'-' => result + value,
-        b'*' => result / value,
-        b'/' => result * value,
-        _ => unreachable!(),
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Operator::Add => '+',
+            Operator::Subtract => '-',
+            Operator::Multiply => '*',
+            Operator::Divide => '/',
+        };
+
+        write!(f, "{}", symbol)
     }
 }
 
-fn solve_right(value: u64, result: u64, operator: u8) -> u64 {
-    match operator {
-        b'+' => result - value,
-        b'-' => value - result,
-        b'*' => result / value,
-        b'/' => value / result,
-        _ => unreachable!(),
+fn divide_exactly(dividend: u64, divisor: u64) -> Option<u64> {
+    match dividend.checked_rem(divisor)? {
+        0 => Some(dividend / divisor),
+        _ => None,
     }
 }
@@ -1,21 +1,57 @@
+use super::{register, Answer, Day, LineParser, Parameters, ParseError};
+use anyhow::{bail, Context};
 use num_enum::{FromPrimitive, IntoPrimitive};
-use std::iter::Peekable;
-use std::str::Bytes;
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(solve(input, false))
+register! {
+    day: Day22,
+    title: "Monkey Map",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    Ok(solve(input, true))
+// Positions are stored in a byte
+const MAX_SIZE: usize = 256;
+
+pub struct Notes {
+    map: Map,
+    path: Vec<Command>,
+}
+
+// The map is followed by a blank line and the path
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Notes> {
+    let (map, path) = match input {
+        [map @ .., blank, path] if !map.is_empty() => {
+            if !blank.is_empty() {
+                let index = input.len() - 2;
+                return Err(LineParser::new(Day::Day22, index, blank)
+                    .error("a blank line")
+                    .into());
+            }
+
+            (Map::parse(map)?, parse_path(input.len() - 1, path)?)
+        }
+        _ => bail!("Expected a map, a blank line and a path"),
+    };
+
+    Ok(Notes { map, path })
+}
+
+pub fn part_a(notes: &Notes) -> anyhow::Result<impl Into<Answer>> {
+    Ok(solve(notes, None))
 }
 
-fn solve(input: &[&str], cube: bool) -> usize {
-    let map = Map::new(&input[..input.len() - 2]);
-    let mut position = map.start();
+pub fn part_b(notes: &Notes) -> anyhow::Result<impl Into<Answer>> {
+    let cube = Cube::new(&notes.map)?;
+    Ok(solve(notes, Some(&cube)))
+}
+
+fn solve(notes: &Notes, cube: Option<&Cube>) -> usize {
+    let map = &notes.map;
+    let mut position = map.start;
     let mut direction = Direction::Right;
 
-    for command in Commands::new(input[input.len() - 1]) {
+    for &command in &notes.path {
         let move_amount = match command {
             Command::Move(move_amount) => move_amount,
             Command::TurnLeft => {
@@ -46,7 +82,7 @@ fn solve(input: &[&str], cube: bool) -> usize {
     1000 * y + 4 * x + direction
 }
 
-#[derive(IntoPrimitive, FromPrimitive, Copy, Clone)]
+#[derive(IntoPrimitive, FromPrimitive, Eq, PartialEq, Copy, Clone)]
 #[repr(u8)]
 enum Direction {
     #[default]
@@ -68,6 +104,13 @@ impl Direction {
     fn turn(self, count: u8) -> Self {
         ((u8::from(self) + count) % 4).into()
     }
+
+    const ALL: [Direction; 4] = [
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+        Direction::Up,
+    ];
 }
 
 #[derive(Copy, Clone)]
@@ -93,41 +136,51 @@ struct Map {
     width: usize,
     height: usize,
     tiles: Vec<Tile>,
+    start: Position,
 }
 
 impl Map {
-    fn new(input: &[&str]) -> Map {
+    fn parse(input: &[&str]) -> anyhow::Result<Map> {
         let height = input.len();
-        let width = input.iter().map(|line| line.len()).max().unwrap();
+        let width = input
+            .iter()
+            .map(|line| line.len())
+            .max()
+            .unwrap_or_default();
+
+        if height > MAX_SIZE {
+            bail!("The map is taller than {} rows", MAX_SIZE);
+        }
 
         let mut tiles = vec![Tile::Air; width * height];
 
         for (y, row) in input.iter().enumerate() {
-            for (x, cell) in row.bytes().enumerate() {
-                let tile = match cell {
-                    b'#' => Tile::Wall,
-                    b'.' => Tile::Ground,
-                    _ => continue,
-                };
+            let mut parser = LineParser::new(Day::Day22, y, row);
 
-                tiles[x + y * width] = tile;
-            }
-        }
+            for x in 0..row.len() {
+                if x == MAX_SIZE {
+                    return Err(parser.error("the end of the line").into());
+                }
 
-        Map {
-            width,
-            height,
-            tiles,
+                tiles[x + y * width] = match parser.one_of(&[' ', '#', '.'])? {
+                    '#' => Tile::Wall,
+                    '.' => Tile::Ground,
+                    _ => Tile::Air,
+                };
+            }
         }
-    }
 
-    fn start(&self) -> Position {
-        let index = self.tiles[..self.width]
+        let start = tiles[..width]
             .iter()
             .position(|tile| *tile == Tile::Ground)
-            .unwrap();
+            .context("The top row of the map has no open tiles")?;
 
-        Position::new(index as u8, 0)
+        Ok(Map {
+            width,
+            height,
+            tiles,
+            start: Position::new(start as u8, 0),
+        })
     }
 
     fn is_on_edge(&self, position: Position, direction: Direction) -> bool {
@@ -143,7 +196,7 @@ impl Map {
         &self,
         position: Position,
         direction: Direction,
-        cube: bool,
+        cube: Option<&Cube>,
     ) -> Option<(Position, Direction)> {
         let (sx, sy) = (position.x as usize, position.y as usize);
 
@@ -157,8 +210,15 @@ impl Map {
         let (mut x, mut y) = ((sx + dx) % self.width, (sy + dy) % self.height);
         let mut tile = self.tiles[x + y * self.width];
 
-        if cube && (tile == Tile::Air || self.is_on_edge(position, direction)) {
-            return self.find_next_cube_position(position, direction);
+        if let Some(cube) = cube {
+            if tile == Tile::Air || self.is_on_edge(position, direction) {
+                let (position, direction) = cube.wrap(position, direction);
+
+                return match self.tiles[position.x as usize + position.y as usize * self.width] {
+                    Tile::Ground => Some((position, direction)),
+                    _ => None,
+                };
+            }
         }
 
         while tile == Tile::Air {
@@ -173,83 +233,211 @@ impl Map {
             None
         }
     }
+}
 
-    fn find_next_cube_position(
-        &self,
-        position: Position,
-        direction: Direction,
-    ) -> Option<(Position, Direction)> {
-        let sx = position.x as usize;
-        let sy = position.y as usize;
-
-        let (x, y, direction) = match direction {
-            Direction::Right => match sy {
-                0..=49 => (99, 149 - sy, Direction::Left),
-                50..=99 => (sy + 50, 49, Direction::Up),
-                100..=149 => (149, 149 - sy, Direction::Left),
-                150..=199 => (sy - 100, 149, Direction::Up),
-                _ => unreachable!(),
-            },
-            Direction::Down => match sx {
-                0..=49 => (sx + 100, 0, Direction::Down),
-                50..=99 => (49, sx + 100, Direction::Left),
-                100..=149 => (99, sx - 50, Direction::Left),
-                _ => unreachable!(),
-            },
-            Direction::Left => match sy {
-                0..=49 => (0, 149 - sy, Direction::Right),
-                50..=99 => (sy - 50, 100, Direction::Down),
-                100..=149 => (50, 149 - sy, Direction::Right),
-                150..=199 => (sy - 100, 0, Direction::Down),
-                _ => unreachable!(),
-            },
-            Direction::Up => match sx {
-                0..=49 => (50, sx + 50, Direction::Right),
-                50..=99 => (0, sx + 100, Direction::Right),
-                100..=149 => (sx - 100, 199, Direction::Up),
-                _ => unreachable!(),
-            },
+// Unit vector in 3D space, used to fold the map into a cube
+type Vector = [i8; 3];
+
+fn negate(vector: Vector) -> Vector {
+    vector.map(|value| -value)
+}
+
+// A face of the cube, along with the 3D directions of moving right and down on the map, as well as
+// the direction the face is facing (its outward normal)
+#[derive(Copy, Clone)]
+struct Face {
+    x: usize,
+    y: usize,
+    right: Vector,
+    down: Vector,
+    normal: Vector,
+}
+
+impl Face {
+    fn heading(&self, direction: Direction) -> Vector {
+        match direction {
+            Direction::Right => self.right,
+            Direction::Down => self.down,
+            Direction::Left => negate(self.right),
+            Direction::Up => negate(self.down),
+        }
+    }
+
+    // Orientation of the neighboring face after folding the map over the edge in the direction
+    fn fold(&self, direction: Direction, x: usize, y: usize) -> Face {
+        let (right, down, normal) = match direction {
+            Direction::Right => (negate(self.normal), self.down, self.right),
+            Direction::Down => (self.right, negate(self.normal), self.down),
+            Direction::Left => (self.normal, self.down, negate(self.right)),
+            Direction::Up => (self.right, self.normal, negate(self.down)),
         };
 
-        if self.tiles[x + y * self.width] == Tile::Ground {
-            Some((Position::new(x as u8, y as u8), direction))
-        } else {
-            None
+        Face {
+            x,
+            y,
+            right,
+            down,
+            normal,
         }
     }
 }
 
-#[derive(Eq, PartialEq)]
-enum Command {
-    Move(usize),
-    TurnLeft,
-    TurnRight,
+struct Cube {
+    size: usize,
+    faces: Vec<Face>,
 }
 
-struct Commands<'a>(Peekable<Bytes<'a>>);
+impl Cube {
+    // Makes sure the map folds into a cube, so that every edge leads to another face
+    fn new(map: &Map) -> anyhow::Result<Self> {
+        let area = map.tiles.iter().filter(|tile| **tile != Tile::Air).count();
+        let size = (1..=MAX_SIZE)
+            .find(|size| size * size * 6 >= area)
+            .filter(|size| size * size * 6 == area)
+            .context("The map does not fold into a cube")?;
+
+        let start = map.start;
+
+        let mut faces = vec![Face {
+            x: start.x as usize / size * size,
+            y: 0,
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+            normal: [0, 0, -1],
+        }];
+
+        let mut index = 0;
+
+        // Walk the net, folding each newly discovered face relative to its neighbor
+        while index < faces.len() {
+            let face = faces[index];
+            index += 1;
+
+            for direction in Direction::ALL {
+                let (x, y) = match direction {
+                    Direction::Right => (face.x + size, face.y),
+                    Direction::Down => (face.x, face.y + size),
+                    Direction::Left if face.x >= size => (face.x - size, face.y),
+                    Direction::Up if face.y >= size => (face.x, face.y - size),
+                    _ => continue,
+                };
+
+                if x >= map.width
+                    || y >= map.height
+                    || map.tiles[x + y * map.width] == Tile::Air
+                    || faces.iter().any(|face| face.x == x && face.y == y)
+                {
+                    continue;
+                }
+
+                faces.push(face.fold(direction, x, y));
+            }
+        }
+
+        // Six full faces cover the whole map, since they add up to its area
+        let full = |face: &Face| {
+            (face.y..face.y + size).all(|y| {
+                (face.x..face.x + size).all(|x| {
+                    x < map.width && y < map.height && map.tiles[x + y * map.width] != Tile::Air
+                })
+            })
+        };
+
+        let distinct = faces.iter().enumerate().all(|(index, face)| {
+            faces[..index]
+                .iter()
+                .all(|other| other.normal != face.normal)
+        });
+
+        if faces.len() != 6 || !faces.iter().all(full) || !distinct {
+            bail!("The map does not fold into a cube");
+        }
 
-impl<'a> Commands<'a> {
-    fn new(input: &'a str) -> Self {
-        Commands(input.bytes().peekable())
+        Ok(Cube { size, faces })
     }
-}
 
-impl Iterator for Commands<'_> {
-    type Item = Command;
+    // Every face faces a different way, so the faces being looked for always exist
+    fn wrap(&self, position: Position, direction: Direction) -> (Position, Direction) {
+        let (x, y) = (position.x as usize, position.y as usize);
+
+        let face = self
+            .faces
+            .iter()
+            .find(|face| {
+                (face.x..face.x + self.size).contains(&x)
+                    && (face.y..face.y + self.size).contains(&y)
+            })
+            .unwrap();
+
+        let heading = face.heading(direction);
+        let target = self
+            .faces
+            .iter()
+            .find(|target| target.normal == heading)
+            .unwrap();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let next = self.0.next()?;
+        // Going over the edge, we end up moving against the normal of the face we've left
+        let next_heading = negate(face.normal);
+        let next_direction = Direction::ALL
+            .into_iter()
+            .find(|direction| target.heading(*direction) == next_heading)
+            .unwrap();
 
-        let mut amount = match next {
-            b'L' => return Some(Command::TurnLeft),
-            b'R' => return Some(Command::TurnRight),
-            _ => (next - b'0') as usize,
+        // The offset along the edge, measured in the direction of the lateral vector
+        let (lateral, offset) = match direction {
+            Direction::Right | Direction::Left => (face.down, y - face.y),
+            Direction::Down | Direction::Up => (face.right, x - face.x),
         };
 
-        while matches!(self.0.peek(), Some(next) if next.is_ascii_digit()) {
-            amount = amount * 10 + (self.0.next().unwrap() - b'0') as usize;
-        }
+        let last = self.size - 1;
+
+        let project = |axis: Vector| {
+            if axis == lateral {
+                offset
+            } else {
+                last - offset
+            }
+        };
+
+        let (dx, dy) = match next_direction {
+            Direction::Right => (0, project(target.down)),
+            Direction::Down => (project(target.right), 0),
+            Direction::Left => (last, project(target.down)),
+            Direction::Up => (project(target.right), last),
+        };
+
+        let position = Position::new((target.x + dx) as u8, (target.y + dy) as u8);
+        (position, next_direction)
+    }
+}
 
-        Some(Command::Move(amount))
+#[derive(Eq, PartialEq, Copy, Clone)]
+enum Command {
+    Move(usize),
+    TurnLeft,
+    TurnRight,
+}
+
+// Reads a path such as "10R5L5"
+fn parse_path(index: usize, line: &str) -> Result<Vec<Command>, ParseError> {
+    let mut parser = LineParser::new(Day::Day22, index, line);
+    let mut commands = Vec::new();
+
+    while !parser.is_empty() || commands.is_empty() {
+        let command = if parser
+            .remaining()
+            .starts_with(|char: char| char.is_ascii_digit())
+        {
+            Command::Move(parser.number()?)
+        } else {
+            match parser.one_of(&['L', 'R'])? {
+                'L' => Command::TurnLeft,
+                _ => Command::TurnRight,
+            }
+        };
+
+        commands.push(command);
     }
+
+    Ok(commands)
 }
@@ -1,6 +1,18 @@
+use super::{register, Answer, Cancellation, Day, LineParser, Parameters};
+use anyhow::bail;
 use std::ops::Add;
 
+register! {
+    day: Day23,
+    title: "Unstable Diffusion",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
 const PADDING: usize = 80;
+// Positions, including the padding, are stored in a byte
+const MAX_SIZE: usize = u8::MAX as usize + 1 - PADDING * 2;
 
 const DIRECTIONS: [(i8, i8); 8] = [
     (0, -1),
@@ -20,25 +32,32 @@ const MOVES: [[usize; 3]; 4] = [
     [1, 2, 3], // east
 ];
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Map> {
+    Map::parse(input)
+}
+
+// Both parts move the elves around, so each of them starts from a copy of the map
+pub fn part_a(map: &Map) -> anyhow::Result<impl Into<Answer>> {
     let mut moves = MOVES;
-    let mut map = Map::new(input);
+    let mut map = map.clone();
 
     for _ in 0..10 {
-        map.simulate(moves);
+        map.simulate(moves)?;
         moves.rotate_left(1);
     }
 
     Ok(map.score())
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
+pub fn part_b(map: &Map) -> anyhow::Result<impl Into<Answer>> {
     let mut moves = MOVES;
-    let mut map = Map::new(input);
+    let mut map = map.clone();
+    let cancellation = Cancellation::current();
 
     let mut round = 1;
 
-    while !map.simulate(moves) {
+    while !map.simulate(moves)? {
+        cancellation.check()?;
         round += 1;
         moves.rotate_left(1);
     }
@@ -69,28 +88,43 @@ impl Add<(i8, i8)> for Position {
     }
 }
 
+#[derive(Clone)]
 struct Elf {
     current_position: Position,
     next_position: Position,
 }
 
-struct Map {
+#[derive(Clone)]
+pub struct Map {
     width: usize,
+    height: usize,
     elves: Vec<Elf>,
     grid: Vec<u8>,
 }
 
 impl Map {
-    fn new(input: &[&str]) -> Self {
-        let width = input[0].len() + PADDING * 2;
+    fn parse(input: &[&str]) -> anyhow::Result<Self> {
+        let columns = input.iter().map(|row| row.len()).max().unwrap_or_default();
+
+        if input.len() > MAX_SIZE {
+            bail!("The grove is taller than {} rows", MAX_SIZE);
+        }
+
+        let width = columns.min(MAX_SIZE) + PADDING * 2;
         let height = input.len() + PADDING * 2;
 
         let mut elves = Vec::new();
         let mut grid = vec![0; width * height];
 
         for (y, row) in input.iter().enumerate() {
-            for (x, cell) in row.bytes().enumerate() {
-                if cell == b'#' {
+            let mut parser = LineParser::new(Day::Day23, y, row);
+
+            for x in 0..row.len() {
+                if x == MAX_SIZE {
+                    return Err(parser.error("the end of the line").into());
+                }
+
+                if parser.one_of(&['#', '.'])? == '#' {
                     let x = x + PADDING;
                     let y = y + PADDING;
 
@@ -110,10 +144,20 @@ impl Map {
             }
         }
 
-        Map { width, elves, grid }
+        if elves.is_empty() {
+            bail!("There are no elves in the grove");
+        }
+
+        Ok(Map {
+            width,
+            height,
+            elves,
+            grid,
+        })
     }
 
-    fn simulate(&mut self, moves: [[usize; 3]; 4]) -> bool {
+    // Moves the elves for a round, returning whether all of them stayed in place
+    fn simulate(&mut self, moves: [[usize; 3]; 4]) -> anyhow::Result<bool> {
         let mut stable = true;
 
         for elf in &mut self.elves {
@@ -152,6 +196,14 @@ impl Map {
             let count = self.grid[index];
 
             if count == 1 {
+                // Elves on the edge could not look around without leaving the grid
+                let Position { x, y } = elf.next_position;
+
+                if x == 0 || y == 0 || x as usize == self.width - 1 || y as usize == self.height - 1
+                {
+                    bail!("The elves spread out more than {} tiles", PADDING - 1);
+                }
+
                 self.grid[elf.current_position.index(self.width)] = 0;
                 self.grid[index] = u8::MAX;
                 elf.current_position = elf.next_position;
@@ -164,7 +216,7 @@ impl Map {
             }
         }
 
-        stable
+        Ok(stable)
     }
 
     fn score(&self) -> usize {
@@ -1,30 +1,50 @@
+use super::{register, Answer, Cancellation, Day, LineParser, Parameters, ParseError};
+use anyhow::bail;
 use std::collections::VecDeque;
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let mut valley = Valley::new(input);
+register! {
+    day: Day24,
+    title: "Blizzard Basin",
+    parse: parse,
+    part_a: part_a,
+    part_b: part_b,
+}
+
+// Positions are stored in a byte and have to stay below the size of the valley
+const MAX_SIZE: usize = u8::MAX as usize;
+
+pub fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Valley> {
+    let mut valley = Valley::parse(input)?;
     valley.predict();
-    Ok(find_path(Position::START, valley.exit(), &mut valley))
+    Ok(valley)
 }
 
-pub fn part_b(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
-    let mut valley = Valley::new(input);
+// Both parts move the blizzards along, so each of them starts from a copy of the valley
+pub fn part_a(valley: &Valley) -> anyhow::Result<impl Into<Answer>> {
+    let mut valley = valley.clone();
+    find_path(Position::START, valley.exit(), &mut valley)
+}
+
+pub fn part_b(valley: &Valley) -> anyhow::Result<impl Into<Answer>> {
+    let mut valley = valley.clone();
     let exit = valley.exit();
-    valley.predict();
 
-    let there = find_path(Position::START, exit, &mut valley);
-    let back = find_path(exit, Position::START, &mut valley);
-    let finish = find_path(Position::START, exit, &mut valley);
+    let there = find_path(Position::START, exit, &mut valley)?;
+    let back = find_path(exit, Position::START, &mut valley)?;
+    let finish = find_path(Position::START, exit, &mut valley)?;
 
     Ok(there + back + finish)
 }
 
 // BFS search for best path
-fn find_path(start: Position, end: Position, valley: &mut Valley) -> usize {
-    let mut minute = 0;
+fn find_path(start: Position, end: Position, valley: &mut Valley) -> anyhow::Result<usize> {
+    let cancellation = Cancellation::current();
+    let mut minute = 0u16;
 
     // Wait until we can make out first move
     while !valley.get(start).will_be_empty() {
-        minute += 1;
+        cancellation.check()?;
+        minute = next_minute(minute)?;
         valley.simulate();
         valley.predict();
     }
@@ -39,7 +59,8 @@ fn find_path(start: Position, end: Position, valley: &mut Valley) -> usize {
         // None means we couldn't find a path
         // But that's not the end since it's valid and safe to stay before the starting position
         if !matches!(&state, Some(state) if state.minute == minute) {
-            minute += 1;
+            cancellation.check()?;
+            minute = next_minute(minute)?;
 
             // Simulate waiting before the starting position
             if valley.get(start).will_be_empty() {
@@ -65,7 +86,7 @@ fn find_path(start: Position, end: Position, valley: &mut Valley) -> usize {
             // Simulate moving out of the valley
             valley.simulate();
             valley.predict();
-            return state.minute as usize + 1;
+            return Ok(state.minute as usize + 1);
         }
 
         for position in state.position.find_moves(valley.width, valley.height) {
@@ -81,6 +102,15 @@ fn find_path(start: Position, end: Position, valley: &mut Valley) -> usize {
     }
 }
 
+// Minutes are counted in 16 bits, which is plenty unless the blizzards never let us through. The
+// last minute is kept free for the move made during it.
+fn next_minute(minute: u16) -> anyhow::Result<u16> {
+    match minute + 1 {
+        u16::MAX => bail!("No path leads through the valley within {} minutes", minute),
+        minute => Ok(minute),
+    }
+}
+
 struct State {
     minute: u16,
     position: Position,
@@ -187,15 +217,16 @@ impl Blizzard {
 struct Tile(u8);
 
 impl Tile {
-    fn new(byte: u8) -> Self {
-        match byte {
-            b'^' => Tile(Blizzard::UP.0),
-            b'>' => Tile(Blizzard::RIGHT.0),
-            b'v' => Tile(Blizzard::DOWN.0),
-            b'<' => Tile(Blizzard::LEFT.0),
-            b'.' => Tile(0),
-            _ => unreachable!(),
-        }
+    fn parse(parser: &mut LineParser) -> Result<Self, ParseError> {
+        let tile = match parser.one_of(&['^', '>', 'v', '<', '.'])? {
+            '^' => Tile(Blizzard::UP.0),
+            '>' => Tile(Blizzard::RIGHT.0),
+            'v' => Tile(Blizzard::DOWN.0),
+            '<' => Tile(Blizzard::LEFT.0),
+            _ => Tile(0),
+        };
+
+        Ok(tile)
     }
 
     fn will_be_empty(self) -> bool {
@@ -215,28 +246,65 @@ impl Tile {
     }
 }
 
-struct Valley {
+#[derive(Clone)]
+pub struct Valley {
     width: usize,
     height: usize,
     tiles: Vec<Tile>,
 }
 
 impl Valley {
-    fn new(input: &[&str]) -> Self {
-        let width = input[0].len() - 2;
-        let height = input.len() - 2;
+    // The valley is surrounded by walls, with the entrance in the top left corner and the exit in
+    // the bottom right one
+    fn parse(input: &[&str]) -> anyhow::Result<Self> {
+        let (first, rows, last) = match input {
+            [first, rows @ .., last] if !rows.is_empty() => (first, rows, last),
+            _ => bail!("Expected a valley with at least one row between the walls"),
+        };
+
+        let width = first.len().saturating_sub(2);
+        let height = rows.len();
+
+        if width == 0 || width > MAX_SIZE || height > MAX_SIZE {
+            bail!(
+                "Expected a valley from 1 to {} tiles wide and tall, found {} by {}",
+                MAX_SIZE,
+                width,
+                height
+            );
+        }
+
+        let wall = "#".repeat(width);
+
+        let mut parser = LineParser::new(Day::Day24, 0, first);
+        parser.expect("#.")?;
+        parser.expect(&wall)?;
+        parser.end()?;
+
+        let mut tiles = Vec::with_capacity(width * height);
 
-        let tiles = input[1..=height]
-            .iter()
-            .flat_map(|row| &row.as_bytes()[1..=width])
-            .map(|byte| Tile::new(*byte))
-            .collect::<Vec<_>>();
+        for (index, row) in rows.iter().enumerate() {
+            let mut parser = LineParser::new(Day::Day24, index + 1, row);
+            parser.expect("#")?;
 
-        Valley {
+            for _ in 0..width {
+                tiles.push(Tile::parse(&mut parser)?);
+            }
+
+            parser.expect("#")?;
+            parser.end()?;
+        }
+
+        let mut parser = LineParser::new(Day::Day24, input.len() - 1, last);
+        parser.expect(&wall)?;
+        parser.expect(".#")?;
+        parser.end()?;
+
+        Ok(Valley {
             width,
             height,
             tiles,
-        }
+        })
     }
 
     fn get(&self, position: Position) -> Tile {
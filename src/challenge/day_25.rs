@@ -1,13 +1,21 @@
+use super::{register, Answer, Day, LineParser, ParseError};
 use std::collections::VecDeque;
 
-pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
+register! {
+    day: Day25,
+    title: "Full of Hot Air",
+    part_a: lines(part_a),
+}
+
+pub fn part_a(input: &[&str]) -> anyhow::Result<impl Into<Answer>> {
     let mut acc = 0;
     let mut exhausted;
     let mut result = VecDeque::new();
 
-    let mut numbers = input
+    let numbers = parse(input)?;
+    let mut numbers = numbers
         .iter()
-        .map(|number| number.bytes().rev())
+        .map(|number| number.iter().rev())
         .collect::<Vec<_>>();
 
     loop {
@@ -19,7 +27,7 @@ pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
                 None => continue,
             };
 
-            acc += parse_snafu(digit);
+            acc += digit;
             exhausted = false;
         }
 
@@ -40,18 +48,36 @@ pub fn part_a(input: &[&str]) -> anyhow::Result<impl std::fmt::Display> {
         acc = carry;
     }
 
+    // The numbers add up to zero
+    if result.is_empty() {
+        result.push_back(b'0');
+    }
+
     Ok(std::str::from_utf8(result.make_contiguous())?.to_string())
 }
 
-fn parse_snafu(digit: u8) -> isize {
-    match digit {
-        b'2' => 2,
-        b'1' => 1,
-        b'0' => 0,
-        b'-' => -1,
-        b'=' => -2,
-        _ => unreachable!(),
+// Reads the digits of every number, from the most significant one
+fn parse(input: &[&str]) -> Result<Vec<Vec<isize>>, ParseError> {
+    let mut numbers = Vec::with_capacity(input.len());
+
+    for (index, line) in input.iter().enumerate() {
+        let mut parser = LineParser::new(Day::Day25, index, line);
+        let mut digits = Vec::with_capacity(line.len());
+
+        while !parser.is_empty() || digits.is_empty() {
+            digits.push(match parser.one_of(&['2', '1', '0', '-', '='])? {
+                '2' => 2,
+                '1' => 1,
+                '0' => 0,
+                '-' => -1,
+                _ => -2,
+            });
+        }
+
+        numbers.push(digits);
     }
+
+    Ok(numbers)
 }
 
 fn format_snafu(digit: isize) -> u8 {
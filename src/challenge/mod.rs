@@ -1,100 +1,76 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::str::FromStr;
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::Context;
 use lazy_static::lazy_static;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use regex::Regex;
 
-mod day_01;
-mod day_02;
-mod day_03;
-mod day_04;
-mod day_05;
-mod day_06;
-mod day_07;
-mod day_08;
-mod day_09;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
-mod day_14;
-mod day_15;
-mod day_16;
-mod day_17;
-mod day_18;
-mod day_19;
-mod day_20;
-mod day_21;
-mod day_22;
-mod day_23;
-mod day_24;
-mod day_25;
+use crate::input::InputSource;
+
+pub use answer::Answer;
+pub use cancellation::{Cancellation, Cancelled};
+pub use parameters::Parameters;
+pub use parser::{LineParser, ParseError};
+pub use puzzle::{Functions, Model, Puzzle, TwoPhase};
+pub(crate) use registry::register;
+pub use registry::{DaySolutions, Registration};
+pub use selector::{select, Selector};
+pub use year::Year;
+
+mod answer;
+mod cancellation;
+pub mod day_01;
+pub mod day_02;
+pub mod day_03;
+pub mod day_04;
+pub mod day_05;
+pub mod day_06;
+pub mod day_07;
+pub mod day_08;
+pub mod day_09;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+pub mod day_17;
+pub mod day_18;
+pub mod day_19;
+pub mod day_20;
+pub mod day_21;
+pub mod day_22;
+pub mod day_23;
+pub mod day_24;
+pub mod day_25;
+mod panic;
+mod parameters;
+mod parser;
+mod puzzle;
+mod registry;
+mod selector;
+mod year;
 
 lazy_static! {
     static ref CHALLENGE_PATTERN: Regex =
-        Regex::new("(?i)(?:Day\\W*)?(\\d\\d?)\\W*([AB])").unwrap();
-    static ref SOLUTIONS: Solutions = {
-        use Day::*;
-        use Part::*;
-
-        let mut solutions = Solutions::new();
-
-        solutions.add(Day01, PartA, day_01::part_a);
-        solutions.add(Day01, PartB, day_01::part_b);
-        solutions.add(Day02, PartA, day_02::part_a);
-        solutions.add(Day02, PartB, day_02::part_b);
-        solutions.add(Day03, PartA, day_03::part_a);
-        solutions.add(Day03, PartB, day_03::part_b);
-        solutions.add(Day04, PartA, day_04::part_a);
-        solutions.add(Day04, PartB, day_04::part_b);
-        solutions.add(Day05, PartA, day_05::part_a);
-        solutions.add(Day05, PartB, day_05::part_b);
-        solutions.add(Day06, PartA, day_06::part_a);
-        solutions.add(Day06, PartB, day_06::part_b);
-        solutions.add(Day07, PartA, day_07::part_a);
-        solutions.add(Day07, PartB, day_07::part_b);
-        solutions.add(Day08, PartA, day_08::part_a);
-        solutions.add(Day08, PartB, day_08::part_b);
-        solutions.add(Day09, PartA, day_09::part_a);
-        solutions.add(Day09, PartB, day_09::part_b);
-        solutions.add(Day10, PartA, day_10::part_a);
-        solutions.add(Day10, PartB, day_10::part_b);
-        solutions.add(Day11, PartA, day_11::part_a);
-        solutions.add(Day11, PartB, day_11::part_b);
-        solutions.add(Day12, PartA, day_12::part_a);
-        solutions.add(Day12, PartB, day_12::part_b);
-        solutions.add(Day13, PartA, day_13::part_a);
-        solutions.add(Day13, PartB, day_13::part_b);
-        solutions.add(Day14, PartA, day_14::part_a);
-        solutions.add(Day14, PartB, day_14::part_b);
-        solutions.add(Day15, PartA, day_15::part_a);
-        solutions.add(Day15, PartB, day_15::part_b);
-        solutions.add(Day16, PartA, day_16::part_a);
-        solutions.add(Day16, PartB, day_16::part_b);
-        solutions.add(Day17, PartA, day_17::part_a);
-        solutions.add(Day17, PartB, day_17::part_b);
-        solutions.add(Day18, PartA, day_18::part_a);
-        solutions.add(Day18, PartB, day_18::part_b);
-        solutions.add(Day19, PartA, day_19::part_a);
-        solutions.add(Day19, PartB, day_19::part_b);
-        solutions.add(Day20, PartA, day_20::part_a);
-        solutions.add(Day20, PartB, day_20::part_b);
-        solutions.add(Day21, PartA, day_21::part_a);
-        solutions.add(Day21, PartB, day_21::part_b);
-        solutions.add(Day22, PartA, day_22::part_a);
-        solutions.add(Day22, PartB, day_22::part_b);
-        solutions.add(Day23, PartA, day_23::part_a);
-        solutions.add(Day23, PartB, day_23::part_b);
-        solutions.add(Day24, PartA, day_24::part_a);
-        solutions.add(Day24, PartB, day_24::part_b);
-        solutions.add(Day25, PartA, day_25::part_a);
-
-        solutions
-    };
+        Regex::new("(?i)^(?:(\\d{4})-)?(?:Day\\W*)?(\\d\\d?)\\W*([AB])$").unwrap();
+    // Every solved challenge, keyed by year, day and part, or why the registrations do not fit
+    static ref SOLUTIONS: Result<Solutions, String> =
+        Solutions::registered(inventory::iter::<Registration>)
+            .map_err(|error| format!("{:#}", error));
+}
+
+// Registry of every solved challenge, which is put together on first use. The binary asks for it
+// before running any command, so that a broken registration is reported straight away.
+pub fn solutions() -> anyhow::Result<&'static Solutions> {
+    SOLUTIONS
+        .as_ref()
+        .map_err(|error| anyhow::anyhow!("Invalid solution registry: {}", error))
 }
 
 #[derive(
@@ -130,47 +106,23 @@ pub enum Day {
 }
 
 impl Day {
-    fn name(&self) -> &'static str {
-        use Day::*;
-
-        match self {
-            Day01 => "Calorie Counting",
-            Day02 => "Rock Paper Scissors",
-            Day03 => "Rucksack Reorganization",
-            Day04 => "Camp Cleanup",
-            Day05 => "Supply Stacks",
-            Day06 => "Tuning Trouble",
-            Day07 => "No Space Left On Device",
-            Day08 => "Treetop Tree House",
-            Day09 => "Rope Bridge",
-            Day10 => "Cathode-Ray Tube",
-            Day11 => "Monkey in the Middle",
-            Day12 => "Hill Climbing Algorithm",
-            Day13 => "Distress Signal",
-            Day14 => "Regolith Reservoir",
-            Day15 => "Beacon Exclusion Zone",
-            Day16 => "Proboscidea Volcanium",
-            Day17 => "Pyroclastic Flow",
-            Day18 => "Boiling Boulders",
-            Day19 => "Not Enough Minerals",
-            Day20 => "Grove Positioning System",
-            Day21 => "Monkey Math",
-            Day22 => "Monkey Map",
-            Day23 => "Unstable Diffusion",
-            Day24 => "Blizzard Basin",
-            Day25 => "Full of Hot Air",
-        }
+    // Puzzles unlock at midnight EST on the given day of December
+    pub fn release_time(&self, year: Year) -> SystemTime {
+        crate::release::release_time(year, *self)
     }
 
-    async fn input(&self) -> anyhow::Result<String> {
-        let index = u8::from(*self);
-        crate::http::get(format!("https://adventofcode.com/2022/day/{}/input", index)).await
+    // Time left until the puzzle unlocks, None once it is available
+    pub fn time_until_release(&self, year: Year) -> Option<Duration> {
+        self.release_time(year)
+            .duration_since(SystemTime::now())
+            .ok()
+            .filter(|remaining| !remaining.is_zero())
     }
 }
 
 impl Display for Day {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Day {:02}: {}", u8::from(*self), self.name())
+        write!(f, "Day {:02}", u8::from(*self))
     }
 }
 
@@ -217,85 +169,396 @@ impl FromStr for Part {
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-pub struct Challenge(Day, Part);
+pub struct Challenge(Year, Day, Part);
 
 impl Challenge {
-    pub fn new(day: Day, part: Part) -> Self {
-        Challenge(day, part)
+    pub fn new(year: Year, day: Day, part: Part) -> Self {
+        Challenge(year, day, part)
     }
-}
 
-impl FromStr for Challenge {
-    type Err = anyhow::Error;
+    pub fn year(&self) -> Year {
+        self.0
+    }
 
-    fn from_str(string: &str) -> Result<Self, Self::Err> {
-        let captures = CHALLENGE_PATTERN
-            .captures(string)
-            .with_context(|| format!("{} is not a valid challenge, expecting \\d+[AB]", string))?;
+    pub fn day(&self) -> Day {
+        self.1
+    }
+
+    pub fn part(&self) -> Part {
+        self.2
+    }
+
+    // Title of the puzzle, when its year is registered
+    pub fn title(&self) -> Option<&'static str> {
+        solutions().ok()?.title(self.0, self.1)
+    }
+
+    // Short form such as 01a, prefixed by the year outside of the default one (2021-01a), which
+    // parses back into the same challenge
+    pub fn id(&self) -> String {
+        let part = match self.2 {
+            Part::PartA => 'a',
+            Part::PartB => 'b',
+        };
+
+        if self.0 == Year::DEFAULT {
+            format!("{:02}{}", u8::from(self.1), part)
+        } else {
+            format!("{}-{:02}{}", self.0, u8::from(self.1), part)
+        }
+    }
+
+    // Parses challenges such as 16a, 2021-16a or day 16 a, using the given year when it is omitted
+    pub fn parse(string: &str, year: Year) -> anyhow::Result<Self> {
+        let captures = CHALLENGE_PATTERN.captures(string.trim()).with_context(|| {
+            format!(
+                "{} is not a valid challenge, expecting [YYYY-]\\d+[AB]",
+                string
+            )
+        })?;
+
+        let year = match captures.get(1) {
+            Some(year) => year.as_str().parse()?,
+            None => year,
+        };
 
         let day = captures
-            .get(1)
+            .get(2)
             .context("Day capture group is missing")?
             .as_str()
             .parse()?;
 
         let part = captures
-            .get(2)
+            .get(3)
             .context("Part capture group is missing")?
             .as_str()
             .parse()?;
 
-        Ok(Challenge(day, part))
+        Ok(Challenge(year, day, part))
+    }
+}
+
+impl FromStr for Challenge {
+    type Err = anyhow::Error;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Challenge::parse(string, Year::DEFAULT)
     }
 }
 
 impl Display for Challenge {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} ({})", self.0, self.1)
+        if self.0 != Year::DEFAULT {
+            write!(f, "{} ", self.0)?;
+        }
+
+        match self.title() {
+            Some(title) => write!(f, "{}: {} ({})", self.1, title, self.2),
+            None => write!(f, "{} ({})", self.1, self.2),
+        }
+    }
+}
+
+// Outcome of solving a single challenge, left to the caller to present. Parsing is shared by both
+// parts of a day, which report the same parse time. Days solved by plain functions parse while
+// solving and have no parse time.
+pub struct Report {
+    pub challenge: Challenge,
+    pub result: Result<Answer, String>,
+    pub parse: Option<Duration>,
+    pub duration: Duration,
+}
+
+impl Report {
+    pub fn answer(&self) -> Option<&Answer> {
+        self.result.as_ref().ok()
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.result.as_ref().err().map(String::as_str)
+    }
+
+    // Report of a challenge which could not even start, such as when its input is missing
+    pub fn failed(challenge: Challenge, error: &anyhow::Error) -> Self {
+        Report {
+            challenge,
+            result: Err(format!("{:#}", error)),
+            parse: None,
+            duration: Duration::ZERO,
+        }
     }
 }
 
-pub async fn solve(challenge: &Challenge) -> anyhow::Result<()> {
-    SOLUTIONS.solve(challenge).await
+// Model parsed from the input of a day, along with the time it took when the puzzle parses on its
+// own
+pub struct Parsed {
+    pub model: Model,
+    pub duration: Option<Duration>,
 }
 
-trait Solution {
-    fn run(&self, challenge: &Challenge, input: &[&str]) -> anyhow::Result<()>;
+pub async fn solve(challenge: &Challenge, source: &InputSource, parameters: &Parameters) -> Report {
+    match solutions() {
+        Ok(solutions) => solutions.solve(challenge, source, parameters).await,
+        Err(error) => Report::failed(*challenge, &error),
+    }
 }
 
-impl<R: Display> Solution for fn(&[&str]) -> anyhow::Result<R> {
-    fn run(&self, challenge: &Challenge, input: &[&str]) -> anyhow::Result<()> {
-        let start = Instant::now();
-        let result = self(input)?;
-        let duration = start.elapsed();
-        println!("{}: {} (duration = {:?})", challenge, result, duration);
-        Ok(())
+// Turns the input of a challenge into its answer. Solutions are implemented as plain functions,
+// which either take the whole input or its lines, optionally followed by the parameters.
+pub trait Solution: Sync {
+    fn solve(&self, input: &str, parameters: &Parameters) -> anyhow::Result<Answer>;
+}
+
+impl<R: Into<Answer>> Solution for fn(&str) -> anyhow::Result<R> {
+    fn solve(&self, input: &str, _: &Parameters) -> anyhow::Result<Answer> {
+        Ok(self(input)?.into())
+    }
+}
+
+impl<R: Into<Answer>> Solution for fn(&[&str]) -> anyhow::Result<R> {
+    fn solve(&self, input: &str, _: &Parameters) -> anyhow::Result<Answer> {
+        let lines = input.lines().collect::<Vec<_>>();
+        Ok(self(&lines)?.into())
+    }
+}
+
+impl<R: Into<Answer>> Solution for fn(&[&str], &Parameters) -> anyhow::Result<R> {
+    fn solve(&self, input: &str, parameters: &Parameters) -> anyhow::Result<Answer> {
+        let lines = input.lines().collect::<Vec<_>>();
+        Ok(self(&lines, parameters)?.into())
     }
 }
 
-struct Solutions(HashMap<Challenge, Box<dyn Solution + Sync + 'static>>);
+// Registry of solutions and puzzle titles, which are scoped by year. Days are either made of
+// solution functions, or of puzzles which parse their input once for both parts.
+#[derive(Default)]
+pub struct Solutions {
+    challenges: HashSet<Challenge>,
+    functions: HashMap<(Year, Day), Functions>,
+    puzzles: HashMap<(Year, Day), Box<dyn Puzzle>>,
+    titles: HashMap<(Year, Day), &'static str>,
+}
 
 impl Solutions {
-    fn new() -> Self {
-        Solutions(HashMap::new())
+    pub fn new() -> Self {
+        Solutions::default()
+    }
+
+    pub fn insert(
+        &mut self,
+        challenge: Challenge,
+        solution: impl Solution + 'static,
+    ) -> anyhow::Result<()> {
+        let Challenge(year, day, part) = challenge;
+
+        if self.challenges.contains(&challenge) {
+            anyhow::bail!("{} {} {} is already registered", year, day, part);
+        }
+
+        self.functions
+            .entry((year, day))
+            .or_default()
+            .insert(part, solution);
+        self.challenges.insert(challenge);
+        Ok(())
     }
 
-    fn add<R: Display + 'static>(
+    // Registers both parts of a day, which share the puzzle's model
+    pub fn insert_puzzle(
         &mut self,
+        year: Year,
         day: Day,
-        part: Part,
-        func: fn(&[&str]) -> anyhow::Result<R>,
-    ) {
-        self.0.insert(Challenge::new(day, part), Box::new(func));
+        puzzle: impl Puzzle + 'static,
+    ) -> anyhow::Result<()> {
+        for part in [Part::PartA, Part::PartB] {
+            if self.challenges.contains(&Challenge(year, day, part)) {
+                anyhow::bail!("{} {} {} is already registered", year, day, part);
+            }
+        }
+
+        self.puzzles.insert((year, day), Box::new(puzzle));
+        self.challenges.insert(Challenge(year, day, Part::PartA));
+        self.challenges.insert(Challenge(year, day, Part::PartB));
+        Ok(())
     }
 
-    async fn solve(&self, challenge: &Challenge) -> anyhow::Result<()> {
-        let solution = self
-            .0
-            .get(challenge)
-            .with_context(|| format!("Cannot find solution for {}", challenge))?;
+    pub fn title(&self, year: Year, day: Day) -> Option<&'static str> {
+        self.titles.get(&(year, day)).copied()
+    }
 
-        let input = challenge.0.input().await?;
-        solution.run(challenge, &input.lines().collect::<Vec<_>>())
+    // Registered challenges, in no particular order
+    pub fn challenges(&self) -> impl Iterator<Item = &Challenge> {
+        self.challenges.iter()
     }
+
+    pub fn get(&self, challenge: &Challenge) -> anyhow::Result<&dyn Puzzle> {
+        if !self.challenges.contains(challenge) {
+            anyhow::bail!("Cannot find solution for {}", challenge);
+        }
+
+        self.puzzle(challenge.0, challenge.1)
+    }
+
+    fn puzzle(&self, year: Year, day: Day) -> anyhow::Result<&dyn Puzzle> {
+        if let Some(puzzle) = self.puzzles.get(&(year, day)) {
+            return Ok(puzzle.as_ref());
+        }
+
+        match self.functions.get(&(year, day)) {
+            Some(functions) => Ok(functions),
+            None => anyhow::bail!("Cannot find solution for {} {}", year, day),
+        }
+    }
+
+    // Parses and solves the challenge, without timing or catching anything
+    pub fn run(
+        &self,
+        challenge: &Challenge,
+        input: &str,
+        parameters: &Parameters,
+    ) -> anyhow::Result<Answer> {
+        let puzzle = self.get(challenge)?;
+        let model = puzzle.parse(input, parameters)?;
+        puzzle.solve(challenge.2, &model, parameters)
+    }
+
+    pub async fn solve(
+        &self,
+        challenge: &Challenge,
+        source: &InputSource,
+        parameters: &Parameters,
+    ) -> Report {
+        match source.read(challenge.0, challenge.1).await {
+            Ok(input) => self.solve_input(challenge, &input, parameters),
+            Err(error) => Report::failed(*challenge, &error),
+        }
+    }
+
+    // Reading the input is not part of the durations. Panics are reported as failures, so that one
+    // broken solution does not take down the others.
+    pub fn solve_input(
+        &self,
+        challenge: &Challenge,
+        input: &str,
+        parameters: &Parameters,
+    ) -> Report {
+        match self.parse(challenge.0, challenge.1, input, parameters) {
+            Ok(parsed) => self.solve_parsed(challenge, &parsed, parameters),
+            Err(error) => Report::failed(*challenge, &error),
+        }
+    }
+
+    pub fn parse(
+        &self,
+        year: Year,
+        day: Day,
+        input: &str,
+        parameters: &Parameters,
+    ) -> anyhow::Result<Parsed> {
+        let puzzle = self.puzzle(year, day)?;
+
+        let start = Instant::now();
+        let model = panic::catch(|| puzzle.parse(input, parameters))
+            .with_context(|| format!("Could not parse the input of {} {}", year, day))?;
+
+        Ok(Parsed {
+            model,
+            duration: puzzle.parses().then(|| start.elapsed()),
+        })
+    }
+
+    pub fn solve_parsed(
+        &self,
+        challenge: &Challenge,
+        parsed: &Parsed,
+        parameters: &Parameters,
+    ) -> Report {
+        let puzzle = match self.get(challenge) {
+            Ok(puzzle) => puzzle,
+            Err(error) => return Report::failed(*challenge, &error),
+        };
+
+        let start = Instant::now();
+        let result = panic::catch(|| puzzle.solve(challenge.2, &parsed.model, parameters));
+
+        Report {
+            challenge: *challenge,
+            result: result.map_err(|error| format!("{:#}", error)),
+            parse: parsed.duration,
+            duration: start.elapsed(),
+        }
+    }
+
+    // Parses the input on a thread of its own, see run_in_thread
+    pub async fn parse_in_thread(
+        &'static self,
+        year: Year,
+        day: Day,
+        input: Arc<str>,
+        parameters: Arc<Parameters>,
+        limit: Option<Duration>,
+    ) -> anyhow::Result<Parsed> {
+        let name = format!("{}-{:02}", year, u8::from(day));
+        run_in_thread(name, limit, move || {
+            self.parse(year, day, &input, &parameters)
+        })
+        .await?
+    }
+
+    // Solves the challenge on a thread of its own, see run_in_thread
+    pub async fn solve_in_thread(
+        &'static self,
+        challenge: Challenge,
+        parsed: Arc<Parsed>,
+        parameters: Arc<Parameters>,
+        limit: Option<Duration>,
+    ) -> Report {
+        let parse = parsed.duration;
+        let result = run_in_thread(challenge.id(), limit, move || {
+            self.solve_parsed(&challenge, &parsed, &parameters)
+        })
+        .await;
+
+        match result {
+            Ok(report) => report,
+            Err(error) => Report {
+                challenge,
+                result: Err(format!("{:#}", error)),
+                parse,
+                duration: limit.unwrap_or_default(),
+            },
+        }
+    }
+}
+
+// Once the time limit runs out the work is abandoned along with its thread, after asking it to
+// cancel. The thread is left to finish on its own, so it does not hold up the next challenge.
+async fn run_in_thread<T: Send + 'static>(
+    name: String,
+    limit: Option<Duration>,
+    work: impl FnOnce() -> T + Send + 'static,
+) -> anyhow::Result<T> {
+    let cancellation = Cancellation::new();
+    let token = cancellation.clone();
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+
+    std::thread::Builder::new()
+        .name(name)
+        .spawn(move || {
+            let _ = sender.send(token.scope(work));
+        })
+        .context("Could not start a thread")?;
+
+    let result = match limit {
+        Some(limit) => match tokio::time::timeout(limit, receiver).await {
+            Ok(result) => result,
+            Err(_) => {
+                cancellation.cancel();
+                anyhow::bail!("timed out after {:?}", limit);
+            }
+        },
+        None => receiver.await,
+    };
+
+    result.context("The thread stopped without a result")
 }
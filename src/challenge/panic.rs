@@ -0,0 +1,69 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::panic::AssertUnwindSafe;
+use std::sync::Once;
+
+thread_local! {
+    // Set while a solution runs on this thread, so that the hook knows its panic will be caught
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    // Message and location of the last caught panic, left by the hook for catch to pick up
+    static PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// Runs the solution, turning a panic into an error with its location and message, such as
+// "panicked at src/challenge/day_06.rs:21:10: called `Option::unwrap()` on a `None` value". The
+// default hook stays quiet for caught panics, as the error already says everything it would print.
+pub fn catch<T>(solution: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<T> {
+    install_hook();
+
+    let catching = CATCHING.with(|catching| catching.replace(true));
+    let result = std::panic::catch_unwind(AssertUnwindSafe(solution));
+    CATCHING.with(|flag| flag.set(catching));
+
+    match result {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = PANIC
+                .with(|panic| panic.borrow_mut().take())
+                .unwrap_or_else(|| format!("panicked: {}", payload_message(payload.as_ref())));
+
+            Err(anyhow::Error::msg(message))
+        }
+    }
+}
+
+fn install_hook() {
+    static HOOK: Once = Once::new();
+
+    HOOK.call_once(|| {
+        let default = std::panic::take_hook();
+
+        std::panic::set_hook(Box::new(move |info| {
+            if !CATCHING.with(Cell::get) {
+                return default(info);
+            }
+
+            let message = match info.location() {
+                Some(location) => format!(
+                    "panicked at {}: {}",
+                    location,
+                    payload_message(info.payload())
+                ),
+                None => format!("panicked: {}", payload_message(info.payload())),
+            };
+
+            PANIC.with(|panic| *panic.borrow_mut() = Some(message));
+        }));
+    });
+}
+
+// Panics carry either a static message or a formatted one
+fn payload_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
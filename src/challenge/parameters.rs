@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::Context;
+
+// Named values which override limits that solutions assume for the real input, such as the row
+// scanned by day 15. The examples from the puzzle descriptions are usually much smaller.
+#[derive(Default, Clone, Debug)]
+pub struct Parameters(HashMap<String, String>);
+
+impl Parameters {
+    pub fn new() -> Self {
+        Parameters(HashMap::new())
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.0.insert(name.into(), value.into());
+    }
+
+    // Parses NAME=VALUE, as passed on the command line
+    pub fn insert_pair(&mut self, pair: &str) -> anyhow::Result<()> {
+        let (name, value) = pair
+            .split_once('=')
+            .with_context(|| format!("{} is not a valid parameter, expecting NAME=VALUE", pair))?;
+
+        self.insert(name, value);
+        Ok(())
+    }
+
+    pub fn get<T>(&self, name: &str, default: T) -> anyhow::Result<T>
+    where
+        T: FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        match self.0.get(name) {
+            Some(value) => value
+                .parse()
+                .with_context(|| format!("{} is not a valid value for {}", value, name)),
+            None => Ok(default),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::Day;
+
+// Longest piece of the offending text shown in an error
+const SNIPPET_LENGTH: usize = 24;
+
+// Points at the part of an input which does not look like the puzzle describes, such as:
+// Day 19, line 3, column 24: expected a number, found "x ore"
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ParseError {
+    pub day: Day,
+    // Line and column both start at 1, like in editors
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, line {}, column {}: expected {}, found ",
+            self.day, self.line, self.column, self.expected
+        )?;
+
+        if self.text.is_empty() {
+            write!(f, "the end of the line")
+        } else {
+            write!(f, "{:?}", self.text)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Reads a single line of an input from left to right, keeping track of the column so that errors
+// can point at it
+pub struct LineParser<'a> {
+    day: Day,
+    line: usize,
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> LineParser<'a> {
+    // Index is the position of the line in the input, starting at 0
+    pub fn new(day: Day, index: usize, text: &'a str) -> Self {
+        LineParser {
+            day,
+            line: index + 1,
+            text,
+            position: 0,
+        }
+    }
+
+    // Byte offset of the next character to read
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining().is_empty()
+    }
+
+    // Error at the current position, showing the text which follows it
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        self.error_at(self.position, expected)
+    }
+
+    pub fn error_at(&self, position: usize, expected: impl Into<String>) -> ParseError {
+        let column = self.text[..position].chars().count() + 1;
+        let text = self.text[position..].chars().take(SNIPPET_LENGTH).collect();
+
+        ParseError {
+            day: self.day,
+            line: self.line,
+            column,
+            text,
+            expected: expected.into(),
+        }
+    }
+
+    // Skips the literal, which has to come next
+    pub fn expect(&mut self, literal: &str) -> Result<(), ParseError> {
+        if !self.remaining().starts_with(literal) {
+            return Err(self.error(format!("{:?}", literal)));
+        }
+
+        self.position += literal.len();
+        Ok(())
+    }
+
+    // Reads one of the given characters
+    pub fn one_of(&mut self, chars: &[char]) -> Result<char, ParseError> {
+        match self.remaining().chars().next() {
+            Some(char) if chars.contains(&char) => {
+                self.position += char.len_utf8();
+                Ok(char)
+            }
+            _ => {
+                let chars = chars.iter().map(char::to_string).collect::<Vec<_>>();
+                Err(self.error(format!("one of {}", chars.join(" "))))
+            }
+        }
+    }
+
+    // Reads an optionally negative whole number
+    pub fn number<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let remaining = self.remaining();
+        let sign = usize::from(remaining.starts_with('-'));
+        let length = sign
+            + remaining[sign..]
+                .bytes()
+                .take_while(u8::is_ascii_digit)
+                .count();
+
+        let number = remaining[..length]
+            .parse()
+            .map_err(|_| self.error("a number"))?;
+
+        self.position += length;
+        Ok(number)
+    }
+
+    // Reads everything up to the next space or the end of the line
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        self.take_until(' ', "a word")
+    }
+
+    // Reads everything up to the delimiter, which is left in place
+    pub fn until(&mut self, delimiter: char) -> Result<&'a str, ParseError> {
+        match self.remaining().find(delimiter) {
+            Some(_) => self.take_until(delimiter, format!("text followed by {:?}", delimiter)),
+            None => Err(self.error_at(self.text.len(), format!("{:?}", delimiter))),
+        }
+    }
+
+    // Fails unless the whole line has been read
+    pub fn end(&self) -> Result<(), ParseError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error("the end of the line"))
+        }
+    }
+
+    fn take_until(
+        &mut self,
+        delimiter: char,
+        expected: impl Into<String>,
+    ) -> Result<&'a str, ParseError> {
+        let remaining = self.remaining();
+        let length = remaining.find(delimiter).unwrap_or(remaining.len());
+
+        if length == 0 {
+            return Err(self.error(expected));
+        }
+
+        self.position += length;
+        Ok(&remaining[..length])
+    }
+}
@@ -0,0 +1,108 @@
+use std::any::Any;
+
+use anyhow::Context;
+
+use super::{Answer, Parameters, Part, Solution};
+
+// Whatever a puzzle parses its input into, shared by both of its parts
+pub type Model = Box<dyn Any + Send + Sync>;
+
+// Solves the parts of a day in two phases. The input is parsed into a model once, which each part
+// then solves on its own, so that parsing and solving can be timed separately.
+pub trait Puzzle: Sync {
+    fn parse(&self, input: &str, parameters: &Parameters) -> anyhow::Result<Model>;
+    fn solve(&self, part: Part, model: &Model, parameters: &Parameters) -> anyhow::Result<Answer>;
+
+    // Whether parse does the parsing, rather than leaving it to solve, so that timing it on its
+    // own means something
+    fn parses(&self) -> bool {
+        true
+    }
+}
+
+// Parts implemented as plain functions of the input. There is nothing to parse up front, so the
+// model is the input itself and parsing happens while solving, which is timed as part of it.
+#[derive(Default)]
+pub struct Functions {
+    part_a: Option<Box<dyn Solution>>,
+    part_b: Option<Box<dyn Solution>>,
+}
+
+impl Functions {
+    pub fn insert(&mut self, part: Part, solution: impl Solution + 'static) {
+        let slot = match part {
+            Part::PartA => &mut self.part_a,
+            Part::PartB => &mut self.part_b,
+        };
+
+        *slot = Some(Box::new(solution));
+    }
+}
+
+impl Puzzle for Functions {
+    fn parse(&self, input: &str, _: &Parameters) -> anyhow::Result<Model> {
+        Ok(Box::new(input.to_string()))
+    }
+
+    fn solve(&self, part: Part, model: &Model, parameters: &Parameters) -> anyhow::Result<Answer> {
+        let input = model
+            .downcast_ref::<String>()
+            .context("The model is not the input")?;
+
+        let solution = match part {
+            Part::PartA => &self.part_a,
+            Part::PartB => &self.part_b,
+        };
+
+        solution
+            .as_ref()
+            .with_context(|| format!("{} is not implemented", part))?
+            .solve(input, parameters)
+    }
+
+    fn parses(&self) -> bool {
+        false
+    }
+}
+
+type PartFn<M> = Box<dyn Fn(&M) -> anyhow::Result<Answer> + Send + Sync>;
+
+// Parts which solve a typed model, parsed from the lines of the input. Limits which the parts
+// depend on are read from the parameters while parsing and kept in the model.
+pub struct TwoPhase<M> {
+    parse: fn(&[&str], &Parameters) -> anyhow::Result<M>,
+    part_a: PartFn<M>,
+    part_b: PartFn<M>,
+}
+
+impl<M: Send + Sync + 'static> TwoPhase<M> {
+    pub fn new<A: Into<Answer> + 'static, B: Into<Answer> + 'static>(
+        parse: fn(&[&str], &Parameters) -> anyhow::Result<M>,
+        part_a: fn(&M) -> anyhow::Result<A>,
+        part_b: fn(&M) -> anyhow::Result<B>,
+    ) -> Self {
+        TwoPhase {
+            parse,
+            part_a: Box::new(move |model| Ok(part_a(model)?.into())),
+            part_b: Box::new(move |model| Ok(part_b(model)?.into())),
+        }
+    }
+}
+
+impl<M: Send + Sync + 'static> Puzzle for TwoPhase<M> {
+    fn parse(&self, input: &str, parameters: &Parameters) -> anyhow::Result<Model> {
+        let lines = input.lines().collect::<Vec<_>>();
+        Ok(Box::new((self.parse)(&lines, parameters)?))
+    }
+
+    fn solve(&self, part: Part, model: &Model, _: &Parameters) -> anyhow::Result<Answer> {
+        let model = model
+            .downcast_ref::<M>()
+            .context("The model was parsed by another puzzle")?;
+
+        match part {
+            Part::PartA => (self.part_a)(model),
+            Part::PartB => (self.part_b)(model),
+        }
+    }
+}
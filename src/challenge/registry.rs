@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+
+use anyhow::bail;
+
+use super::{Answer, Challenge, Day, Parameters, Part, Solution, Solutions, TwoPhase, Year};
+
+// Solutions of a single day, which its module submits with register! so that the registry can be
+// put together without listing every day by hand
+pub struct Registration {
+    pub year: Year,
+    pub day: Day,
+    pub title: &'static str,
+    // Module which submitted the registration, named in errors
+    pub module: &'static str,
+    pub register: fn(&mut DaySolutions) -> anyhow::Result<()>,
+}
+
+inventory::collect!(Registration);
+
+// Registers the solutions of the day, along with its title:
+//
+// register! {
+//     day: Day01,
+//     title: "Calorie Counting",
+//     part_a: lines(part_a),
+//     part_b: text(part_b),
+// }
+//
+// Parts take the lines of the input, the whole text or the lines and parameters (lines, text or
+// lines_with_parameters). Days which parse their input once for both parts list parse, part_a and
+// part_b functions instead, where parse takes the lines and parameters and the parts take the
+// model. Days of another year than the default one start with its number, such as year: 2023.
+macro_rules! register {
+    (@part part_a) => {
+        $crate::challenge::Part::PartA
+    };
+    (@part part_b) => {
+        $crate::challenge::Part::PartB
+    };
+    (
+        @year $year:expr,
+        day: $day:ident,
+        title: $title:literal,
+        parse: $parse:path,
+        part_a: $part_a:path,
+        part_b: $part_b:path $(,)?
+    ) => {
+        ::inventory::submit! {
+            $crate::challenge::Registration {
+                year: $year,
+                day: $crate::challenge::Day::$day,
+                title: $title,
+                module: module_path!(),
+                register: |day| day.parsed($parse, $part_a, $part_b),
+            }
+        }
+    };
+    (
+        @year $year:expr,
+        day: $day:ident,
+        title: $title:literal,
+        $($part:ident: $kind:ident($solution:path)),+ $(,)?
+    ) => {
+        ::inventory::submit! {
+            $crate::challenge::Registration {
+                year: $year,
+                day: $crate::challenge::Day::$day,
+                title: $title,
+                module: module_path!(),
+                register: |day| {
+                    $(day.$kind($crate::challenge::register!(@part $part), $solution)?;)+
+                    Ok(())
+                },
+            }
+        }
+    };
+    (year: $year:literal, $($rest:tt)+) => {
+        $crate::challenge::register! {
+            @year $crate::challenge::Year::new_const($year),
+            $($rest)+
+        }
+    };
+    (day: $day:ident, $($rest:tt)+) => {
+        $crate::challenge::register! {
+            @year $crate::challenge::Year::DEFAULT,
+            day: $day,
+            $($rest)+
+        }
+    };
+}
+
+pub(crate) use register;
+
+// Parts registered by a single registration, which keeps track of them to catch parts registered
+// twice or not at all
+pub struct DaySolutions<'a> {
+    solutions: &'a mut Solutions,
+    challenge: Challenge,
+    parts: Vec<Part>,
+}
+
+impl DaySolutions<'_> {
+    pub fn lines<R: Into<Answer> + 'static>(
+        &mut self,
+        part: Part,
+        func: fn(&[&str]) -> anyhow::Result<R>,
+    ) -> anyhow::Result<()> {
+        self.insert(part, func)
+    }
+
+    pub fn text<R: Into<Answer> + 'static>(
+        &mut self,
+        part: Part,
+        func: fn(&str) -> anyhow::Result<R>,
+    ) -> anyhow::Result<()> {
+        self.insert(part, func)
+    }
+
+    pub fn lines_with_parameters<R: Into<Answer> + 'static>(
+        &mut self,
+        part: Part,
+        func: fn(&[&str], &Parameters) -> anyhow::Result<R>,
+    ) -> anyhow::Result<()> {
+        self.insert(part, func)
+    }
+
+    pub fn parsed<M, A, B>(
+        &mut self,
+        parse: fn(&[&str], &Parameters) -> anyhow::Result<M>,
+        part_a: fn(&M) -> anyhow::Result<A>,
+        part_b: fn(&M) -> anyhow::Result<B>,
+    ) -> anyhow::Result<()>
+    where
+        M: Send + Sync + 'static,
+        A: Into<Answer> + 'static,
+        B: Into<Answer> + 'static,
+    {
+        // Parts registered more than once are reported along with the module once all are in
+        if self.parts.is_empty() {
+            let Challenge(year, day, _) = self.challenge;
+            let puzzle = TwoPhase::new(parse, part_a, part_b);
+            self.solutions.insert_puzzle(year, day, puzzle)?;
+        }
+
+        self.parts.extend([Part::PartA, Part::PartB]);
+        Ok(())
+    }
+
+    fn insert(&mut self, part: Part, solution: impl Solution + 'static) -> anyhow::Result<()> {
+        if !self.parts.contains(&part) {
+            let Challenge(year, day, _) = self.challenge;
+            self.solutions
+                .insert(Challenge::new(year, day, part), solution)?;
+        }
+
+        self.parts.push(part);
+        Ok(())
+    }
+}
+
+impl Solutions {
+    // Puts together the registry out of every registration, checking that each day is registered
+    // once with all of its parts. Days may be missing, as they are only written once released.
+    pub fn registered<'a>(
+        registrations: impl IntoIterator<Item = &'a Registration>,
+    ) -> anyhow::Result<Self> {
+        let mut days = BTreeMap::<(Year, Day), &Registration>::new();
+
+        for registration in registrations {
+            let key = (registration.year, registration.day);
+
+            if let Some(existing) = days.insert(key, registration) {
+                let mut modules = [existing.module, registration.module];
+                modules.sort_unstable();
+
+                bail!(
+                    "{} {} is registered by both {} and {}",
+                    registration.year,
+                    registration.day,
+                    modules[0],
+                    modules[1]
+                );
+            }
+        }
+
+        let mut solutions = Solutions::new();
+
+        for (&(year, day), registration) in &days {
+            let mut day_solutions = DaySolutions {
+                solutions: &mut solutions,
+                challenge: Challenge::new(year, day, Part::PartA),
+                parts: Vec::new(),
+            };
+
+            (registration.register)(&mut day_solutions)?;
+            check_parts(registration, &day_solutions.parts)?;
+            solutions.titles.insert((year, day), registration.title);
+        }
+
+        Ok(solutions)
+    }
+}
+
+// Every day has two parts, apart from the last one, whose second star is given for all the others
+fn check_parts(registration: &Registration, parts: &[Part]) -> anyhow::Result<()> {
+    let expected: &[Part] = match registration.day {
+        Day::Day25 => &[Part::PartA],
+        _ => &[Part::PartA, Part::PartB],
+    };
+
+    for part in [Part::PartA, Part::PartB] {
+        let count = parts
+            .iter()
+            .filter(|registered| **registered == part)
+            .count();
+
+        if count > 1 {
+            bail!(
+                "{} registers {} {} {} more than once",
+                registration.module,
+                registration.year,
+                registration.day,
+                part
+            );
+        }
+
+        if count == 0 && expected.contains(&part) {
+            bail!(
+                "{} does not register {} {} {}",
+                registration.module,
+                registration.year,
+                registration.day,
+                part
+            );
+        }
+    }
+
+    Ok(())
+}
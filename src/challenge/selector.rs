@@ -0,0 +1,182 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{solutions, Challenge, Day, Part, Year};
+
+lazy_static! {
+    static ref YEAR_PATTERN: Regex = Regex::new("^(\\d{4})(?:-(.+))?$").unwrap();
+    static ref DAY_PATTERN: Regex = Regex::new("(?i)^(?:Day\\W*)?(\\d\\d?)$").unwrap();
+    static ref RANGE_PATTERN: Regex =
+        Regex::new("(?i)^(\\d\\d?)([AB])?(?:-|\\.\\.)(\\d\\d?)([AB])?$").unwrap();
+}
+
+// Selects challenges to run, one of: all, odd, even, 5 (or day 5), 1-10, 12a..15b or 16a. Any of
+// them may be prefixed by a year such as 2021-16a, while a year on its own selects all of it.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Selector {
+    All(Year),
+    Odd(Year),
+    Even(Year),
+    Challenge(Challenge),
+    Range(Challenge, Challenge),
+}
+
+impl Selector {
+    // Arguments may hold several selectors separated by commas, while "day" may be passed as a
+    // separate argument from its number. Selectors without a year use the given one.
+    pub fn parse_all(
+        args: impl IntoIterator<Item = String>,
+        year: Year,
+    ) -> anyhow::Result<Vec<Selector>> {
+        let mut selectors = Vec::new();
+        let mut day = None;
+
+        for arg in args {
+            for token in arg.split(',').map(str::trim) {
+                if token.is_empty() {
+                    continue;
+                }
+
+                if token.eq_ignore_ascii_case("day") {
+                    day = Some(token.to_string());
+                    continue;
+                }
+
+                match day.take() {
+                    Some(day) => {
+                        selectors.push(Selector::parse(&format!("{} {}", day, token), year)?)
+                    }
+                    None => selectors.push(Selector::parse(token, year)?),
+                }
+            }
+        }
+
+        if let Some(day) = day {
+            bail!("{} is missing the day number", day);
+        }
+
+        Ok(selectors)
+    }
+
+    fn matches(&self, challenge: &Challenge) -> bool {
+        match self {
+            Selector::All(year) => challenge.year() == *year,
+            Selector::Odd(year) => challenge.year() == *year && u8::from(challenge.day()) % 2 == 1,
+            Selector::Even(year) => challenge.year() == *year && u8::from(challenge.day()) % 2 == 0,
+            Selector::Challenge(selected) => selected == challenge,
+            Selector::Range(start, end) => (start..=end).contains(&challenge),
+        }
+    }
+
+    pub fn parse(string: &str, year: Year) -> anyhow::Result<Self> {
+        let string = string.trim();
+
+        if let Some(captures) = YEAR_PATTERN.captures(string) {
+            let year = captures
+                .get(1)
+                .context("Year capture group is missing")?
+                .as_str()
+                .parse::<Year>()?;
+
+            return match captures.get(2) {
+                Some(selector) => Selector::parse(selector.as_str(), year),
+                None => Ok(Selector::All(year)),
+            };
+        }
+
+        if string.eq_ignore_ascii_case("all") {
+            return Ok(Selector::All(year));
+        } else if string.eq_ignore_ascii_case("odd") {
+            return Ok(Selector::Odd(year));
+        } else if string.eq_ignore_ascii_case("even") {
+            return Ok(Selector::Even(year));
+        }
+
+        if let Some(captures) = DAY_PATTERN.captures(string) {
+            let day = captures
+                .get(1)
+                .context("Day capture group is missing")?
+                .as_str()
+                .parse::<Day>()?;
+
+            let start = Challenge(year, day, Part::PartA);
+            let end = Challenge(year, day, Part::PartB);
+            return Ok(Selector::Range(start, end));
+        }
+
+        if let Some(captures) = RANGE_PATTERN.captures(string) {
+            let bound = |day: usize, part: usize, default: Part| -> anyhow::Result<Challenge> {
+                let day = captures
+                    .get(day)
+                    .context("Day capture group is missing")?
+                    .as_str()
+                    .parse()?;
+
+                let part = match captures.get(part) {
+                    Some(part) => part.as_str().parse()?,
+                    None => default,
+                };
+
+                Ok(Challenge(year, day, part))
+            };
+
+            let start = bound(1, 2, Part::PartA)?;
+            let end = bound(3, 4, Part::PartB)?;
+
+            if start > end {
+                bail!(
+                    "{} is not a valid range, {} comes after {}",
+                    string,
+                    start,
+                    end
+                );
+            }
+
+            return Ok(Selector::Range(start, end));
+        }
+
+        Challenge::parse(string, year)
+            .map(Selector::Challenge)
+            .with_context(|| {
+                format!(
+                    "{} is not a valid selector, expecting all, odd, even, a year, a day, a range or a challenge",
+                    string
+                )
+            })
+    }
+}
+
+impl FromStr for Selector {
+    type Err = anyhow::Error;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Selector::parse(string, Year::DEFAULT)
+    }
+}
+
+// Expands the selectors into the registered challenges, in day and part order
+pub fn select(selectors: &[Selector]) -> anyhow::Result<Vec<Challenge>> {
+    let solutions = solutions()?;
+    let mut challenges = BTreeSet::new();
+
+    for selector in selectors {
+        match selector {
+            // Explicitly selected challenges have to exist
+            Selector::Challenge(challenge) => {
+                solutions.get(challenge)?;
+                challenges.insert(*challenge);
+            }
+            _ => challenges.extend(
+                solutions
+                    .challenges()
+                    .filter(|challenge| selector.matches(challenge)),
+            ),
+        }
+    }
+
+    Ok(challenges.into_iter().collect())
+}
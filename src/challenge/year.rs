@@ -0,0 +1,62 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+
+// Year of an Advent of Code event, the first one took place in 2015
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Year(u16);
+
+impl Year {
+    // Used whenever a challenge does not say otherwise
+    pub const DEFAULT: Year = Year(2022);
+
+    pub fn new(year: u16) -> anyhow::Result<Self> {
+        if year < 2015 {
+            bail!(
+                "{} is not a valid year, the first event took place in 2015",
+                year
+            );
+        }
+
+        Ok(Year(year))
+    }
+
+    // Same check as new, which fails to compile when the year is a constant such as the one passed
+    // to register!
+    pub const fn new_const(year: u16) -> Self {
+        assert!(year >= 2015, "The first event took place in 2015");
+        Year(year)
+    }
+}
+
+impl Default for Year {
+    fn default() -> Self {
+        Year::DEFAULT
+    }
+}
+
+impl From<Year> for u16 {
+    fn from(year: Year) -> Self {
+        year.0
+    }
+}
+
+impl Display for Year {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Year {
+    type Err = anyhow::Error;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let year = string
+            .trim()
+            .parse::<u16>()
+            .with_context(|| format!("{} is not a valid year", string))?;
+
+        Year::new(year)
+    }
+}
@@ -1,17 +1,201 @@
+use std::fmt::Display;
+use std::sync::OnceLock;
+use std::time::Duration;
+
 use anyhow::Context;
+use reqwest::header::HeaderValue;
+use reqwest::{RequestBuilder, StatusCode};
+
+use crate::session::Credentials;
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+// The Advent of Code maintainers ask automated tools to identify themselves
+pub const USER_AGENT: &str = concat!(
+    "github.com/medja/advent-of-code-2022 v",
+    env!("CARGO_PKG_VERSION")
+);
+
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    // Limit for a whole request, including reading the response
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    // Number of additional attempts after a transient failure, only downloads are retried
+    pub retries: u32,
+    // Wait before the first retry, doubled after every following one up to max_backoff
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    pub user_agent: String,
+    pub credentials: Credentials,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
+            retries: 3,
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            user_agent: USER_AGENT.to_string(),
+            credentials: Credentials::from_env(),
+        }
+    }
+}
+
+// Returned when the site does not accept the session cookie anymore, callers can downcast to it
+#[derive(Debug)]
+pub struct SessionExpired;
+
+impl Display for SessionExpired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The session has expired or is not valid, log in again and update the session"
+        )
+    }
+}
+
+impl std::error::Error for SessionExpired {}
+
+pub struct Client {
+    client: reqwest::Client,
+    config: Config,
+}
+
+// Result of a single attempt, only transient failures are worth another one
+enum Attempt {
+    Done(anyhow::Result<String>),
+    Retry(anyhow::Error),
+}
+
+impl Client {
+    pub fn new(config: Config) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .connect_timeout(config.connect_timeout)
+            .user_agent(&config.user_agent)
+            .build()
+            .context("Could not create the HTTP client")?;
+
+        Ok(Client { client, config })
+    }
+
+    pub fn credentials(&self) -> &Credentials {
+        &self.config.credentials
+    }
 
-pub async fn get(url: impl reqwest::IntoUrl) -> anyhow::Result<String> {
-    let session = std::env::var("SESSION").context("SESSION is not defined")?;
-    let cookie = format!("session={}", session);
+    pub async fn get(&self, url: &str) -> anyhow::Result<String> {
+        let cookie = self.session_cookie()?;
+        self.send(self.config.retries, || {
+            self.client.get(url).header("cookie", &cookie)
+        })
+        .await
+        .with_context(|| format!("Could not get {}", url))
+    }
 
-    let response = reqwest::Client::default()
-        .get(url)
-        .header("cookie", cookie)
-        .send()
-        .await?
-        .error_for_status()?
-        .text()
-        .await?;
+    // Submits a form, the site expects the same session cookie as for downloads. Posts are never
+    // retried, the site may have received one which failed and an answer must not be sent twice.
+    pub async fn post(&self, url: &str, form: &[(&str, &str)]) -> anyhow::Result<String> {
+        let cookie = self.session_cookie()?;
+        self.send(0, || {
+            self.client.post(url).header("cookie", &cookie).form(form)
+        })
+        .await
+        .with_context(|| format!("Could not post to {}", url))
+    }
+
+    // Marked as sensitive, so that the session is left out of any debug output
+    fn session_cookie(&self) -> anyhow::Result<HeaderValue> {
+        let (session, _) = self.config.credentials.resolve()?;
+        let mut cookie = HeaderValue::from_str(&session.cookie())
+            .context("The session is not a valid header value")?;
+        cookie.set_sensitive(true);
+        Ok(cookie)
+    }
+
+    // Sends the request, making up to retries more attempts after transient failures
+    async fn send(
+        &self,
+        retries: u32,
+        request: impl Fn() -> RequestBuilder,
+    ) -> anyhow::Result<String> {
+        let mut backoff = self.config.backoff;
+
+        for _ in 0..retries {
+            match attempt(request()).await {
+                Attempt::Done(result) => return result,
+                Attempt::Retry(_) => {
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(self.config.max_backoff);
+                }
+            }
+        }
+
+        match attempt(request()).await {
+            Attempt::Done(result) => result,
+            Attempt::Retry(error) if retries == 0 => Err(error),
+            Attempt::Retry(error) => {
+                Err(error.context(format!("Giving up after {} attempts", retries + 1)))
+            }
+        }
+    }
+}
+
+async fn attempt(request: RequestBuilder) -> Attempt {
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(error) if error.is_timeout() || error.is_connect() || error.is_request() => {
+            return Attempt::Retry(error.into())
+        }
+        Err(error) => return Attempt::Done(Err(error.into())),
+    };
+
+    let status = response.status();
+
+    let body = match response.text().await {
+        Ok(body) => body,
+        Err(error) if error.is_timeout() => return Attempt::Retry(error.into()),
+        Err(error) => return Attempt::Done(Err(error.into())),
+    };
+
+    if body.contains("Puzzle inputs differ by user") && body.contains("Please log in") {
+        return Attempt::Done(Err(SessionExpired.into()));
+    }
+
+    if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+        return Attempt::Retry(anyhow::anyhow!("Server responded with {}", status));
+    }
+
+    if !status.is_success() {
+        return Attempt::Done(Err(anyhow::anyhow!("Server responded with {}", status)));
+    }
+
+    Attempt::Done(Ok(body))
+}
+
+// Sets up the shared client, which has to happen before it is first used
+pub fn configure(config: Config) -> anyhow::Result<()> {
+    let client = Client::new(config)?;
+
+    CLIENT
+        .set(client)
+        .map_err(|_| anyhow::anyhow!("The HTTP client has already been configured"))
+}
+
+// Shared client, using the default configuration unless configured before
+pub fn client() -> anyhow::Result<&'static Client> {
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+
+    let client = Client::new(Config::default())?;
+    Ok(CLIENT.get_or_init(|| client))
+}
 
-    Ok(response)
+pub async fn get(url: &str) -> anyhow::Result<String> {
+    client()?.get(url).await
 }
@@ -0,0 +1,87 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+use crate::cache::Cache;
+use crate::challenge::{Day, Year};
+
+pub enum InputSource {
+    /// Downloads the official input, going through the on-disk cache
+    Download(Cache),
+    /// Reads the same file for every day
+    File(PathBuf),
+    /// Reads dayNN.txt files from a directory, or from its YYYY subdirectory when there is one
+    Directory(PathBuf),
+    /// Reads stdin once, then reuses it for every day
+    Stdin(String),
+}
+
+impl InputSource {
+    // "-" stands for stdin, anything else has to be an existing file or directory
+    pub fn from_path(path: PathBuf) -> anyhow::Result<Self> {
+        if path.as_os_str() == "-" {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .context("Could not read input from stdin")?;
+            return Ok(InputSource::Stdin(input));
+        }
+
+        let metadata = std::fs::metadata(&path)
+            .with_context(|| format!("Could not access {}", path.display()))?;
+
+        if metadata.is_dir() {
+            Ok(InputSource::Directory(path))
+        } else {
+            Ok(InputSource::File(path))
+        }
+    }
+
+    pub async fn read(&self, year: Year, day: Day) -> anyhow::Result<String> {
+        match self {
+            InputSource::Download(cache) => download(year, day, cache).await,
+            InputSource::File(path) => read_file(path),
+            InputSource::Directory(directory) => {
+                let name = format!("day{:02}.txt", u8::from(day));
+                let path = directory.join(year.to_string()).join(&name);
+
+                if path.exists() {
+                    read_file(&path)
+                } else {
+                    read_file(&directory.join(name))
+                }
+            }
+            InputSource::Stdin(input) => Ok(input.clone()),
+        }
+    }
+}
+
+async fn download(year: Year, day: Day, cache: &Cache) -> anyhow::Result<String> {
+    if let Some(input) = cache.read(year, day)? {
+        return Ok(input);
+    }
+
+    crate::release::check_released(year, day)?;
+    let client = crate::http::client()?;
+
+    client.credentials().resolve().with_context(|| {
+        format!(
+            "Input for {} {} is not cached at {}",
+            year,
+            day,
+            cache.path(year, day).display()
+        )
+    })?;
+
+    let index = u8::from(day);
+    let url = format!("{}/{}/day/{}/input", crate::http::BASE_URL, year, index);
+    let input = client.get(&url).await?;
+
+    cache.write(year, day, &input)?;
+    Ok(input)
+}
+
+fn read_file(path: &PathBuf) -> anyhow::Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))
+}
@@ -0,0 +1,284 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::time::{Duration, UNIX_EPOCH};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::cache::Cache;
+use crate::challenge::{Day, Part, Year};
+use crate::http::Client;
+use crate::release::{format_countdown, format_timestamp};
+
+// The site asks tools not to request a leaderboard more than once every 15 minutes
+pub const MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+// Private leaderboard, as returned by /YYYY/leaderboard/private/view/ID.json
+#[derive(Deserialize, Clone, Debug)]
+pub struct Leaderboard {
+    pub owner_id: u64,
+    pub event: String,
+    // Keyed by the id of the member
+    pub members: BTreeMap<String, Member>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Member {
+    pub id: u64,
+    // Members who did not set a name are shown as anonymous
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u64,
+    pub global_score: u64,
+    // Zero until the first star
+    pub last_star_ts: u64,
+    // Stars by day and part, such as {"1": {"1": {...}, "2": {...}}}
+    pub completion_day_level: BTreeMap<u8, BTreeMap<u8, Star>>,
+}
+
+#[derive(Deserialize, Copy, Clone, Debug)]
+pub struct Star {
+    pub get_star_ts: u64,
+    pub star_index: u64,
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Sort {
+    /// Highest local score first, like the site
+    Local,
+    /// Most stars first
+    Stars,
+    /// Alphabetically by name
+    Name,
+    /// Most recent star first
+    Last,
+}
+
+// Reads the leaderboard from the cache while it is fresh, otherwise downloads it again. Only
+// valid leaderboards are cached, so that a login page is never mistaken for one.
+pub async fn fetch(
+    client: &Client,
+    base_url: &str,
+    cache: &Cache,
+    year: Year,
+    id: u64,
+) -> anyhow::Result<String> {
+    if let Some(json) = cache.read_leaderboard(year, id, MAX_AGE)? {
+        return Ok(json);
+    }
+
+    let url = format!("{}/{}/leaderboard/private/view/{}.json", base_url, year, id);
+    let json = client
+        .get(&url)
+        .await
+        .with_context(|| format!("Could not download leaderboard {}", id))?;
+
+    Leaderboard::parse(&json)?;
+    cache.write_leaderboard(year, id, &json)?;
+    Ok(json)
+}
+
+impl Leaderboard {
+    pub fn parse(json: &str) -> anyhow::Result<Self> {
+        serde_json::from_str(json)
+            .context("The leaderboard is not valid, check its id and that the session may view it")
+    }
+
+    pub fn sorted(&self, sort: Sort) -> Vec<&Member> {
+        let mut members = self.members.values().collect::<Vec<_>>();
+
+        // Ties are broken like on the site, by whoever got there first
+        members.sort_by_key(|member| {
+            (
+                Reverse(member.local_score),
+                Reverse(member.stars),
+                member.last_star_ts,
+                member.id,
+            )
+        });
+
+        match sort {
+            Sort::Local => {}
+            Sort::Stars => members.sort_by_key(|member| Reverse(member.stars)),
+            Sort::Name => members.sort_by_key(|member| member.name().to_lowercase()),
+            Sort::Last => members.sort_by_key(|member| Reverse(member.last_star_ts)),
+        }
+
+        members
+    }
+
+    // Member with the given id or name, names of anonymous members being (anonymous user #id)
+    pub fn find(&self, user: &str) -> Option<&Member> {
+        self.members
+            .values()
+            .find(|member| member.id.to_string() == user || member.name() == user)
+    }
+
+    // Last day anyone has a star for, so that the table does not show days nobody reached
+    fn last_day(&self) -> u8 {
+        self.members
+            .values()
+            .flat_map(|member| member.completion_day_level.keys())
+            .copied()
+            .max()
+            .unwrap_or(1)
+    }
+
+    // Table of members with their local score, stars and the time of their last star. Days show
+    // * for both stars, + for the first one only and . for none.
+    pub fn render(&self, sort: Sort) -> String {
+        let days = 1..=self.last_day();
+        let members = self.sorted(sort);
+
+        let rows = members
+            .iter()
+            .enumerate()
+            .map(|(index, member)| {
+                let grid = days
+                    .clone()
+                    .map(|day| match member.completion_day_level.get(&day) {
+                        Some(parts) if parts.len() > 1 => '*',
+                        Some(_) => '+',
+                        None => '.',
+                    })
+                    .collect::<String>();
+
+                let last = match member.last_star_ts {
+                    0 => "-".to_string(),
+                    timestamp => format_timestamp(timestamp),
+                };
+
+                [
+                    format!("{})", index + 1),
+                    member.name(),
+                    member.local_score.to_string(),
+                    member.stars.to_string(),
+                    grid,
+                    last,
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let tens = days
+            .clone()
+            .map(|day| match day / 10 {
+                0 => ' ',
+                tens => char::from(b'0' + tens),
+            })
+            .collect::<String>();
+        let ones = days
+            .map(|day| char::from(b'0' + day % 10))
+            .collect::<String>();
+
+        let headers = [
+            ["", "", "", "", tens.as_str(), ""],
+            [
+                "",
+                "Name",
+                "Score",
+                "Stars",
+                ones.as_str(),
+                "Last star (UTC)",
+            ],
+        ];
+
+        let mut widths = [0; 6];
+
+        for row in headers
+            .iter()
+            .map(|row| row.map(str::to_string))
+            .chain(rows.clone())
+        {
+            for (width, cell) in widths.iter_mut().zip(&row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let line = |row: [&str; 6]| {
+            let line = format!(
+                "{:>w0$} {:w1$}  {:>w2$}  {:>w3$}  {:w4$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                row[4],
+                row[5],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+                w3 = widths[3],
+                w4 = widths[4],
+            );
+
+            format!("{}\n", line.trim_end())
+        };
+
+        // Tens are only needed from day 10 onwards
+        let skip = usize::from(tens.trim().is_empty());
+        let mut table = headers[skip..]
+            .iter()
+            .map(|row| line(*row))
+            .collect::<String>();
+
+        for row in &rows {
+            table.push_str(&line(row.each_ref().map(String::as_str)));
+        }
+
+        table
+    }
+
+    // Time each member took to get their stars, counted from the release of the puzzle
+    pub fn render_times(&self, year: Year, sort: Sort) -> String {
+        let mut output = String::new();
+
+        for member in self.sorted(sort) {
+            output.push_str(&format!("{}\n", member.name()));
+
+            for &day in member.completion_day_level.keys() {
+                let day = match Day::try_from(day) {
+                    Ok(day) => day,
+                    Err(_) => continue,
+                };
+
+                let times = [Part::PartA, Part::PartB]
+                    .map(|part| match member.time_to_star(year, day, part) {
+                        Some(time) => format!("{:>11}", format_countdown(time)),
+                        None => format!("{:>11}", "-"),
+                    })
+                    .join("  ");
+
+                output.push_str(&format!("  {}  {}\n", day, times));
+            }
+        }
+
+        output
+    }
+}
+
+impl Member {
+    pub fn name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    // Seconds since the epoch when the star was collected
+    pub fn star(&self, day: Day, part: Part) -> Option<u64> {
+        let level = match part {
+            Part::PartA => 1,
+            Part::PartB => 2,
+        };
+
+        self.completion_day_level
+            .get(&u8::from(day))?
+            .get(&level)
+            .map(|star| star.get_star_ts)
+    }
+
+    // Time between the release of the puzzle and the star, for puzzles solved after their release
+    pub fn time_to_star(&self, year: Year, day: Day, part: Part) -> Option<Duration> {
+        let star = UNIX_EPOCH + Duration::from_secs(self.star(day, part)?);
+        star.duration_since(day.release_time(year)).ok()
+    }
+}
@@ -0,0 +1,23 @@
+// Solutions to the Advent of Code 2022 challenges, along with everything needed to fetch their
+// inputs and check their answers. The binary is a thin command line client over this crate.
+pub mod answers;
+pub mod bench;
+pub mod cache;
+pub mod challenge;
+pub mod http;
+pub mod input;
+pub mod leaderboard;
+pub mod ocr;
+pub mod output;
+pub mod page;
+pub mod release;
+pub mod runner;
+pub mod scaffold;
+pub mod session;
+pub mod stats;
+pub mod submit;
+
+pub use challenge::{
+    solutions, Answer, Cancellation, Challenge, Day, Parameters, ParseError, Part, Report,
+    Solution, Solutions, Year,
+};
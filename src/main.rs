@@ -1,21 +1,671 @@
-use std::io::BufRead;
+use std::collections::BTreeSet;
+use std::io::{BufRead, Write};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-mod challenge;
-mod http;
+use anyhow::{bail, Context};
+use clap::{Parser, Subcommand};
+
+use advent_of_code_2022::answers::Answers;
+use advent_of_code_2022::bench::{self, Baseline, Comparison};
+use advent_of_code_2022::cache::Cache;
+use advent_of_code_2022::challenge::{self, Challenge, Day, Parameters, Part, Selector, Year};
+use advent_of_code_2022::http;
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::leaderboard::{self, Leaderboard};
+use advent_of_code_2022::output::Format;
+use advent_of_code_2022::page::{self, Page};
+use advent_of_code_2022::release;
+use advent_of_code_2022::runner;
+use advent_of_code_2022::scaffold;
+use advent_of_code_2022::session::{self, Credentials};
+use advent_of_code_2022::stats::Stats;
+use advent_of_code_2022::submit::{self, Outcome, Submissions};
+
+#[derive(Parser)]
+#[command(about = "Solves Advent of Code 2022 challenges")]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    solve: SolveArgs,
+
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Time challenges over many iterations and compare them with a baseline
+    Bench(BenchArgs),
+    /// Solve a challenge and submit its answer to the Advent of Code site
+    Submit(SubmitArgs),
+    /// Check that the session still works and show who it belongs to
+    #[command(alias = "check-session")]
+    Whoami,
+    /// Create the module of a new day, along with its example and answer entries
+    New(NewArgs),
+    /// Show the description of a puzzle, including the second part once it is unlocked
+    Read(ReadArgs),
+    /// Show a private leaderboard, which is downloaded at most every 15 minutes
+    Leaderboard(LeaderboardArgs),
+    /// Show when each star was collected and how fast the solutions run, as a calendar or CSV
+    Stats(StatsArgs),
+}
+
+// Options shared by every command, which decide where inputs come from
+#[derive(clap::Args)]
+struct InputArgs {
+    /// Event of challenges given without a year, others can be selected as 2021-16a
+    #[arg(long, default_value_t = Year::DEFAULT, global = true)]
+    year: Year,
+
+    /// Directory where downloaded inputs are cached, one subdirectory per year [default:
+    /// ~/.cache/aoc]
+    #[arg(long, env = "AOC_CACHE_DIR", global = true)]
+    cache_dir: Option<PathBuf>,
+
+    /// Discard the cached inputs of the given challenges and download them again
+    #[arg(long, global = true)]
+    refresh: bool,
+
+    /// Read inputs from a file, a directory of dayNN.txt files or stdin (-) instead of downloading
+    #[arg(long, value_name = "PATH", conflicts_with = "refresh", global = true)]
+    input: Option<PathBuf>,
+
+    /// Override a limit assumed for the real input, such as row=10 for day 15
+    #[arg(long = "param", value_name = "NAME=VALUE", global = true)]
+    parameters: Vec<String>,
+
+    /// Seconds before a request to the Advent of Code site is abandoned
+    #[arg(long, value_name = "SECONDS", default_value_t = 30, global = true)]
+    timeout: u64,
+
+    /// Number of times a download is retried after a transient failure, answers are sent once
+    #[arg(long, default_value_t = 3, global = true)]
+    retries: u32,
+
+    /// Wait for puzzles which are still locked instead of refusing to download them
+    #[arg(long, global = true, conflicts_with = "input")]
+    wait: bool,
+
+    /// Session cookie, used when SESSION is not set
+    #[arg(long, value_name = "TOKEN", global = true)]
+    session: Option<String>,
+
+    /// File with the session cookie, used when neither SESSION, --session nor
+    /// ~/.config/aoc/session is set
+    #[arg(long, value_name = "PATH", global = true)]
+    session_file: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct SolveArgs {
+    /// Challenges to solve, such as 16b, day 5, 1-10, 12a..15b, odd, even, all or 2021-16b (read
+    /// from stdin when omitted)
+    challenges: Vec<String>,
+
+    /// Compare the answers with the known-good ones in the answers file
+    #[arg(long, conflicts_with = "record")]
+    verify: bool,
+
+    /// Store the answers in the answers file
+    #[arg(long)]
+    record: bool,
+
+    /// File with known-good answers, keyed by challenge
+    #[arg(long, value_name = "PATH", default_value = "answers.toml")]
+    answers: PathBuf,
+
+    /// How to print the answers, the summary and other messages go to stderr unless it is text
+    #[arg(long, value_enum, default_value = "text")]
+    format: Format,
+
+    /// Number of inputs read and solutions run at the same time
+    #[arg(short, long, default_value = "1")]
+    jobs: NonZeroUsize,
+
+    /// Also show the pictures which answers were read from, such as the day 10 screen
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Seconds a challenge may run before it is reported as timed out and left behind
+    #[arg(long, value_name = "SECONDS")]
+    time_limit: Option<u64>,
+}
+
+#[derive(clap::Args)]
+struct BenchArgs {
+    /// Challenges to time, using the same selectors as when solving
+    #[arg(required = true)]
+    challenges: Vec<String>,
+
+    /// Number of measured runs of every challenge
+    #[arg(short = 'n', long, default_value_t = 100)]
+    iterations: usize,
+
+    /// Number of runs before measuring, which are not part of the statistics
+    #[arg(long, default_value_t = 3)]
+    warmup: usize,
+
+    /// Compare the medians with the ones stored in this baseline file
+    #[arg(long, value_name = "PATH")]
+    baseline: Option<PathBuf>,
+
+    /// Store the medians in this baseline file, keeping other challenges already in it
+    #[arg(long, value_name = "PATH")]
+    save_baseline: Option<PathBuf>,
+
+    /// Slowdown of the median over the baseline, in percent, which counts as a regression
+    #[arg(long, default_value_t = 10.0)]
+    threshold: f64,
+}
+
+#[derive(clap::Args)]
+struct SubmitArgs {
+    /// Challenge to submit, such as 16b or 2021-16b
+    challenge: String,
+
+    /// Answer to submit instead of the one found by solving the challenge
+    #[arg(long)]
+    answer: Option<String>,
+
+    /// File with every submitted answer and its outcome, used to never resend a wrong answer
+    #[arg(long, value_name = "PATH", default_value = "submissions.toml")]
+    submissions: PathBuf,
+
+    /// File with known-good answers, correct answers are added to it
+    #[arg(long, value_name = "PATH", default_value = "answers.toml")]
+    answers: PathBuf,
+}
+
+#[derive(clap::Args)]
+struct NewArgs {
+    /// Day to create, such as 5
+    day: Day,
+
+    /// Title of the puzzle, used instead of fetching it from the puzzle page
+    #[arg(long)]
+    title: Option<String>,
+
+    /// File with known-good answers, empty entries are added for the new day
+    #[arg(long, value_name = "PATH", default_value = "answers.toml")]
+    answers: PathBuf,
+}
+
+#[derive(clap::Args)]
+struct ReadArgs {
+    /// Day to show, such as 5
+    day: Day,
+
+    /// How to show the description
+    #[arg(long, value_enum, default_value = "text")]
+    format: page::Format,
+
+    /// Also save the examples of the puzzle as fixtures in tests/examples, keeping existing ones
+    #[arg(long)]
+    examples: bool,
+
+    /// File with every submitted answer, a cached description is downloaded again once it records
+    /// a correct answer to the first part
+    #[arg(long, value_name = "PATH", default_value = "submissions.toml")]
+    submissions: PathBuf,
+}
+
+#[derive(clap::Args)]
+struct LeaderboardArgs {
+    /// Id of the private leaderboard, the number at the end of its address
+    #[arg(env = "AOC_LEADERBOARD")]
+    id: u64,
+
+    /// Order of the members
+    #[arg(long, value_enum, default_value = "local")]
+    sort: leaderboard::Sort,
+
+    /// Also show how long after the release every member got their stars
+    #[arg(long, conflicts_with = "json")]
+    times: bool,
+
+    /// Print the leaderboard as the site returned it
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+struct StatsArgs {
+    /// Id of the private leaderboard which the stars are read from
+    #[arg(env = "AOC_LEADERBOARD")]
+    id: u64,
+
+    /// Member to show, by id or name [default: the user of the session]
+    #[arg(long)]
+    user: Option<String>,
+
+    /// Leave out the runtimes instead of solving every challenge once
+    #[arg(long)]
+    no_run: bool,
+
+    /// Print comma-separated values with one row per day
+    #[arg(long)]
+    csv: bool,
+}
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    if std::env::args().count() > 1 {
-        run(std::env::args().skip(1).map(Ok)).await
+    let args = Args::parse();
+
+    // Every command relies on the registry, so broken registrations are reported before any work
+    challenge::solutions()?;
+
+    http::configure(http::Config {
+        timeout: Duration::from_secs(args.input.timeout),
+        retries: args.input.retries,
+        credentials: Credentials {
+            flag: args.input.session.clone(),
+            token_file: args.input.session_file.clone(),
+            ..Credentials::from_env()
+        },
+        ..http::Config::default()
+    })?;
+
+    match args.command {
+        Some(Command::Bench(bench)) => run_bench(bench, args.input).await,
+        Some(Command::Submit(submit)) => run_submit(submit, args.input).await,
+        Some(Command::Whoami) => run_whoami(args.input).await,
+        Some(Command::New(new)) => run_new(new, args.input).await,
+        Some(Command::Read(read)) => run_read(read, args.input).await,
+        Some(Command::Leaderboard(leaderboard)) => run_leaderboard(leaderboard, args.input).await,
+        Some(Command::Stats(stats)) => run_stats(stats, args.input).await,
+        None => run_solve(args.solve, args.input).await,
+    }
+}
+
+async fn run_solve(args: SolveArgs, input: InputArgs) -> anyhow::Result<()> {
+    if args.challenges.is_empty() && matches!(&input.input, Some(path) if path.as_os_str() == "-") {
+        bail!("Challenges have to be passed as arguments when the input is read from stdin")
+    }
+
+    let selectors = if args.challenges.is_empty() {
+        let lines = std::io::stdin().lock().lines();
+        Selector::parse_all(lines.collect::<Result<Vec<_>, _>>()?, input.year)?
     } else {
-        run(std::io::stdin().lock().lines()).await
+        Selector::parse_all(args.challenges, input.year)?
+    };
+
+    let challenges = challenge::select(&selectors)?;
+    let (source, parameters) = prepare(&input, &challenges).await?;
+    let start = Instant::now();
+
+    if let Some(header) = args.format.header() {
+        println!("{}", header);
+    }
+
+    let reports = runner::run(
+        &challenges,
+        source,
+        parameters,
+        args.jobs.get(),
+        args.time_limit.map(Duration::from_secs),
+        |report| {
+            println!("{}", args.format.format(report, args.verbose)?);
+            Ok(())
+        },
+    )
+    .await?;
+    let elapsed = start.elapsed();
+
+    // Keep the answers alone on stdout, so that they can be piped into other tools
+    let mut messages: Box<dyn Write> = match args.format {
+        Format::Text => Box::new(std::io::stdout()),
+        _ => Box::new(std::io::stderr()),
+    };
+
+    if args.format == Format::Text && reports.len() > 1 {
+        runner::print_summary(&mut messages, &reports, elapsed)?;
+    }
+
+    let failed = reports
+        .iter()
+        .filter(|report| report.error().is_some())
+        .map(|report| report.challenge)
+        .collect::<Vec<_>>();
+
+    if reports.len() > 1 {
+        runner::print_results(&mut messages, reports.len(), &failed)?;
+    }
+
+    if args.verify {
+        runner::verify(&mut messages, &Answers::load(args.answers)?, &reports)?;
+    } else if args.record {
+        runner::record(&mut messages, Answers::load(args.answers)?, &reports)?;
     }
+
+    if !failed.is_empty() {
+        bail!(
+            "{} of {} challenges could not be solved",
+            failed.len(),
+            reports.len()
+        );
+    }
+
+    Ok(())
 }
 
-async fn run(args: impl Iterator<Item = std::io::Result<String>>) -> anyhow::Result<()> {
-    for arg in args {
-        challenge::solve(&arg?.parse()?).await?;
+async fn run_bench(args: BenchArgs, input: InputArgs) -> anyhow::Result<()> {
+    if args.iterations == 0 {
+        bail!("Benchmarks need at least one iteration");
+    }
+
+    let challenges = challenge::select(&Selector::parse_all(args.challenges, input.year)?)?;
+    let (source, parameters) = prepare(&input, &challenges).await?;
+
+    let baseline = args.baseline.map(Baseline::load).transpose()?;
+    let mut saved = match args.save_baseline {
+        Some(path) if path.exists() => Some(Baseline::load(path)?),
+        Some(path) => Some(Baseline::new(path)),
+        None => None,
+    };
+
+    let threshold = args.threshold / 100.0;
+    let mut regressions = 0;
+
+    println!(
+        "{} iterations after {} warm-up runs",
+        args.iterations, args.warmup
+    );
+
+    let solutions = challenge::solutions()?;
+
+    for challenge in &challenges {
+        solutions.get(challenge)?;
+        let input = source.read(challenge.year(), challenge.day()).await?;
+        let measurement = bench::measure(
+            solutions,
+            challenge,
+            &input,
+            &parameters,
+            args.warmup,
+            args.iterations,
+        )
+        .with_context(|| format!("Could not benchmark {}", challenge))?;
+
+        // Baselines compare whole runs, which stay comparable whichever way a day parses
+        print!("{}: {}", challenge, measurement.total);
+
+        if let Some(baseline) = &baseline {
+            let comparison = baseline.compare(challenge, &measurement.total, threshold);
+
+            if let Comparison::Regression(_) = comparison {
+                regressions += 1;
+            }
+
+            print!(", baseline = {}", comparison);
+        }
+
+        println!();
+
+        if let Some(parse) = &measurement.parse {
+            println!("  parse: {}", parse);
+            println!("  solve: {}", measurement.solve);
+        }
+
+        if let Some(saved) = &mut saved {
+            saved.insert(challenge, &measurement.total);
+        }
+    }
+
+    if let Some(saved) = saved {
+        saved.save()?;
+    }
+
+    if regressions > 0 {
+        bail!(
+            "{} of {} challenges regressed by more than {}%",
+            regressions,
+            challenges.len(),
+            args.threshold
+        );
+    }
+
+    Ok(())
+}
+
+async fn run_submit(args: SubmitArgs, input: InputArgs) -> anyhow::Result<()> {
+    let challenge = Challenge::parse(&args.challenge, input.year)?;
+
+    if input.wait {
+        release::wait(challenge.year(), challenge.day()).await;
+    }
+
+    let answer = match args.answer {
+        Some(answer) => answer,
+        None => {
+            let (source, parameters) = prepare(&input, &[challenge]).await?;
+            let report = challenge::solve(&challenge, &source, &parameters).await;
+            report.result.map_err(anyhow::Error::msg)?.submission()?
+        }
+    };
+
+    let mut submissions = Submissions::load(args.submissions)?;
+    submissions.check(&challenge, &answer)?;
+
+    let outcome = submit::submit(http::client()?, http::BASE_URL, &challenge, &answer).await?;
+    submissions.record(&challenge, &answer, &outcome);
+    submissions.save()?;
+
+    match outcome {
+        Outcome::Correct => {
+            let mut answers = Answers::load(args.answers)?;
+            answers.insert(&challenge, answer.clone());
+            answers.save()?;
+            println!("{}: {} is {}", challenge, answer, outcome);
+        }
+        Outcome::AlreadySolved => println!("{}: {}", challenge, outcome),
+        _ => bail!("{}: {} is {}", challenge, answer, outcome),
     }
 
     Ok(())
 }
+
+async fn run_whoami(input: InputArgs) -> anyhow::Result<()> {
+    let client = http::client()?;
+    let (_, source) = client.credentials().resolve()?;
+    let user = session::whoami(client, http::BASE_URL, input.year).await?;
+
+    println!("Logged in as {} (session from {})", user, source);
+    Ok(())
+}
+
+async fn run_new(args: NewArgs, input: InputArgs) -> anyhow::Result<()> {
+    let root = std::env::current_dir().context("Could not find the current directory")?;
+
+    // Only ask the site for the title once it is clear that the day can be created
+    scaffold::check_new(&root, input.year, args.day)?;
+
+    let title = match args.title {
+        Some(title) => title,
+        None => {
+            scaffold::fetch_title(http::client()?, http::BASE_URL, input.year, args.day).await?
+        }
+    };
+
+    let mut answers = Answers::load(args.answers)?;
+
+    for path in scaffold::create(&root, &mut answers, input.year, args.day, &title)? {
+        println!("Wrote {}", path.display());
+    }
+
+    answers.save()?;
+    println!("Created {} {}: {}", input.year, args.day, title);
+    Ok(())
+}
+
+async fn run_read(args: ReadArgs, input: InputArgs) -> anyhow::Result<()> {
+    let cache = match &input.cache_dir {
+        Some(directory) => Cache::new(directory),
+        None => Cache::from_env()?,
+    };
+
+    if input.wait {
+        release::wait(input.year, args.day).await;
+    }
+
+    let html = if input.refresh {
+        None
+    } else {
+        cache.read_page(input.year, args.day)?
+    };
+
+    let part_a = Challenge::new(input.year, args.day, Part::PartA);
+    let part_a_solved = Submissions::load(args.submissions)?.is_solved(&part_a);
+
+    let html = match html {
+        Some(html) if !page::is_stale(&html, part_a_solved) => html,
+        _ => {
+            page::fetch(
+                http::client()?,
+                http::BASE_URL,
+                &cache,
+                input.year,
+                args.day,
+            )
+            .await?
+        }
+    };
+
+    let url = page::url(http::BASE_URL, input.year, args.day);
+    let page = Page::parse(&html, &url)?;
+    print!("{}", page.render(args.format));
+
+    if args.examples {
+        let root = std::env::current_dir().context("Could not find the current directory")?;
+
+        for path in scaffold::save_examples(&root, input.year, args.day, &page.examples())? {
+            eprintln!("Wrote {}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_leaderboard(args: LeaderboardArgs, input: InputArgs) -> anyhow::Result<()> {
+    let cache = match &input.cache_dir {
+        Some(directory) => Cache::new(directory),
+        None => Cache::from_env()?,
+    };
+
+    let json =
+        leaderboard::fetch(http::client()?, http::BASE_URL, &cache, input.year, args.id).await?;
+
+    if args.json {
+        println!("{}", json.trim_end());
+        return Ok(());
+    }
+
+    let leaderboard = Leaderboard::parse(&json)?;
+    print!("{}", leaderboard.render(args.sort));
+
+    if args.times {
+        println!();
+        print!("{}", leaderboard.render_times(input.year, args.sort));
+    }
+
+    Ok(())
+}
+
+async fn run_stats(args: StatsArgs, input: InputArgs) -> anyhow::Result<()> {
+    let cache = match &input.cache_dir {
+        Some(directory) => Cache::new(directory),
+        None => Cache::from_env()?,
+    };
+
+    let client = http::client()?;
+    let json = leaderboard::fetch(client, http::BASE_URL, &cache, input.year, args.id).await?;
+    let leaderboard = Leaderboard::parse(&json)?;
+
+    let user = match args.user {
+        Some(user) => user,
+        None => session::whoami(client, http::BASE_URL, input.year).await?,
+    };
+
+    let member = leaderboard
+        .find(&user)
+        .with_context(|| format!("{} is not a member of leaderboard {}", user, args.id))?;
+
+    // Only released days with a registered solution can be solved
+    let challenges = if args.no_run {
+        Vec::new()
+    } else {
+        challenge::select(&Selector::parse_all(["all".to_string()], input.year)?)?
+            .into_iter()
+            .filter(|challenge| challenge.day().time_until_release(input.year).is_none())
+            .collect()
+    };
+
+    let (source, parameters) = prepare(&input, &challenges).await?;
+    let mut reports = Vec::with_capacity(challenges.len());
+
+    for challenge in &challenges {
+        let report = challenge::solve(challenge, &source, &parameters).await;
+
+        if let Some(error) = report.error() {
+            eprintln!("{}: error: {}", challenge, error);
+        }
+
+        reports.push(report);
+    }
+
+    let stats = Stats::new(input.year, member, &reports);
+
+    if args.csv {
+        print!("{}", stats.to_csv());
+    } else {
+        print!("{}", stats.render_calendar());
+        println!();
+        print!("{}", stats.render_table());
+    }
+
+    Ok(())
+}
+
+// Builds the input source and parameters, discarding the cached inputs first when refreshing and
+// counting down to the release of locked puzzles when waiting
+async fn prepare(
+    args: &InputArgs,
+    challenges: &[Challenge],
+) -> anyhow::Result<(InputSource, Parameters)> {
+    let mut parameters = Parameters::new();
+
+    for pair in &args.parameters {
+        parameters.insert_pair(pair)?;
+    }
+
+    let source = match &args.input {
+        Some(path) => InputSource::from_path(path.clone())?,
+        None => match &args.cache_dir {
+            Some(directory) => InputSource::Download(Cache::new(directory)),
+            None => InputSource::Download(Cache::from_env()?),
+        },
+    };
+
+    if let InputSource::Download(cache) = &source {
+        let days = challenges
+            .iter()
+            .map(|challenge| (challenge.year(), challenge.day()))
+            .collect::<BTreeSet<_>>();
+
+        for (year, day) in days {
+            if args.wait {
+                release::wait(year, day).await;
+            }
+
+            if args.refresh {
+                cache.invalidate(year, day)?;
+            }
+        }
+    }
+
+    Ok((source, parameters))
+}
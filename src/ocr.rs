@@ -0,0 +1,231 @@
+use anyhow::bail;
+
+// Glyphs of the fonts used by puzzles which draw their answer, such as 2022 day 10 (4x6) and 2018
+// day 10 (6x10). Only the letters which have appeared in puzzles so far are known.
+const SMALL_FONT: [(char, [&str; 6]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const LARGE_FONT: [(char, [&str; 10]); 15] = [
+    (
+        'A',
+        [
+            "..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'B',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#",
+            "#....#", "#####.",
+        ],
+    ),
+    (
+        'C',
+        [
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#....#", ".####.",
+        ],
+    ),
+    (
+        'E',
+        [
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'F',
+        [
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'G',
+        [
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#",
+            "#...##", ".###.#",
+        ],
+    ),
+    (
+        'H',
+        [
+            "#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'J',
+        [
+            "...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.",
+            "#...#.", ".###..",
+        ],
+    ),
+    (
+        'K',
+        [
+            "#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..",
+            "#...#.", "#....#",
+        ],
+    ),
+    (
+        'L',
+        [
+            "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'N',
+        [
+            "#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##",
+            "#...##", "#....#",
+        ],
+    ),
+    (
+        'P',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'R',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'X',
+        [
+            "#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'Z',
+        [
+            "######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+];
+
+// Reads the letters drawn with # (lit) and . (dark), picking the font by the number of rows
+pub fn read(rows: &[impl AsRef<str>]) -> anyhow::Result<String> {
+    let grid = rows
+        .iter()
+        .map(|row| row.as_ref().chars().map(|char| char == '#').collect())
+        .collect::<Vec<_>>();
+
+    recognize(&grid)
+}
+
+// Reads the letters of a grid of lit pixels. Letters are told apart by the blank columns between
+// them, as glyphs such as I and Y are narrower or wider than the rest of the font.
+pub fn recognize(grid: &[Vec<bool>]) -> anyhow::Result<String> {
+    let glyphs: Vec<(char, Vec<Vec<bool>>)> = match grid.len() {
+        6 => SMALL_FONT
+            .iter()
+            .map(|(letter, rows)| (*letter, parse_glyph(rows)))
+            .collect(),
+        10 => LARGE_FONT
+            .iter()
+            .map(|(letter, rows)| (*letter, parse_glyph(rows)))
+            .collect(),
+        height => bail!(
+            "Pictures {} rows high cannot be read, expecting 6 or 10 rows",
+            height
+        ),
+    };
+
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |row: &Vec<bool>, column: usize| row.get(column).copied().unwrap_or(false);
+    let blank = |column: usize| grid.iter().all(|row| !lit(row, column));
+
+    let mut letters = String::new();
+    let mut column = 0;
+
+    while column < width {
+        if blank(column) {
+            column += 1;
+            continue;
+        }
+
+        let start = column;
+
+        while column < width && !blank(column) {
+            column += 1;
+        }
+
+        let glyph = grid
+            .iter()
+            .map(|row| (start..column).map(|column| lit(row, column)).collect())
+            .collect::<Vec<Vec<bool>>>();
+
+        match glyphs.iter().find(|(_, known)| *known == glyph) {
+            Some((letter, _)) => letters.push(*letter),
+            None => bail!("Unknown glyph at column {}:\n{}", start, draw_glyph(&glyph)),
+        }
+    }
+
+    if letters.is_empty() {
+        bail!("The picture does not contain any letters");
+    }
+
+    Ok(letters)
+}
+
+// Drops the blank columns around the glyph, which is how it is cut out of a picture
+fn parse_glyph(rows: &[&str]) -> Vec<Vec<bool>> {
+    let grid = rows
+        .iter()
+        .map(|row| row.chars().map(|char| char == '#').collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let width = grid[0].len();
+    let used = (0..width)
+        .filter(|column| grid.iter().any(|row| row[*column]))
+        .collect::<Vec<_>>();
+
+    let (first, last) = match (used.first(), used.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return grid,
+    };
+
+    grid.into_iter()
+        .map(|row| row[first..=last].to_vec())
+        .collect()
+}
+
+fn draw_glyph(glyph: &[Vec<bool>]) -> String {
+    glyph
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|lit| if *lit { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,118 @@
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::challenge::{Answer, Part, Report};
+
+#[derive(ValueEnum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Format {
+    /// One human-readable line per challenge, followed by a summary
+    Text,
+    /// One JSON object per line
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+// Fields emitted for every challenge by the machine-readable formats. JSON keeps the kind of the
+// answer, a number, a string or an array of rows.
+#[derive(Serialize)]
+struct Record<'a> {
+    year: u16,
+    day: u8,
+    part: char,
+    name: &'static str,
+    answer: Option<&'a Answer>,
+    // Missing for days which parse while solving
+    parse_ns: Option<u128>,
+    duration_ns: u128,
+    error: Option<&'a str>,
+}
+
+impl<'a> From<&'a Report> for Record<'a> {
+    fn from(report: &'a Report) -> Self {
+        let part = match report.challenge.part() {
+            Part::PartA => 'a',
+            Part::PartB => 'b',
+        };
+
+        Record {
+            year: report.challenge.year().into(),
+            day: report.challenge.day().into(),
+            part,
+            name: report.challenge.title().unwrap_or_default(),
+            answer: report.answer(),
+            parse_ns: report.parse.map(|parse| parse.as_nanos()),
+            duration_ns: report.duration.as_nanos(),
+            error: report.error(),
+        }
+    }
+}
+
+impl Format {
+    pub fn header(&self) -> Option<&'static str> {
+        match self {
+            Format::Csv => Some("year,day,part,name,answer,parse_ns,duration_ns,error"),
+            _ => None,
+        }
+    }
+
+    // Verbose text output also shows the pictures which answers were read from
+    pub fn format(&self, report: &Report, verbose: bool) -> anyhow::Result<String> {
+        let line = match self {
+            Format::Text => {
+                let timing = match report.parse {
+                    Some(parse) => format!("parse = {:?}, solve = {:?}", parse, report.duration),
+                    None => format!("solve = {:?}", report.duration),
+                };
+
+                match &report.result {
+                    // Pictures such as the day 10 screen start on their own line
+                    Ok(answer) if answer.is_grid() => {
+                        format!("{}: ({})\n{}", report.challenge, timing, answer)
+                    }
+                    Ok(answer) => match answer.picture() {
+                        Some(picture) if verbose => format!(
+                            "{}: {} ({})\n{}",
+                            report.challenge,
+                            answer,
+                            timing,
+                            picture.join("\n")
+                        ),
+                        _ => format!("{}: {} ({})", report.challenge, answer, timing),
+                    },
+                    Err(error) => format!("{}: error: {}", report.challenge, error),
+                }
+            }
+            Format::Json => serde_json::to_string(&Record::from(report))?,
+            Format::Csv => {
+                let record = Record::from(report);
+
+                [
+                    record.year.to_string(),
+                    record.day.to_string(),
+                    record.part.to_string(),
+                    escape_csv(record.name),
+                    escape_csv(&record.answer.map(Answer::to_string).unwrap_or_default()),
+                    record
+                        .parse_ns
+                        .map(|parse| parse.to_string())
+                        .unwrap_or_default(),
+                    record.duration_ns.to_string(),
+                    escape_csv(record.error.unwrap_or_default()),
+                ]
+                .join(",")
+            }
+        };
+
+        Ok(line)
+    }
+}
+
+// Quotes the field when it contains a separator, a quote or a line break (day 10 answers do)
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,396 @@
+use anyhow::{bail, Context};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::cache::Cache;
+use crate::challenge::{Day, Year};
+use crate::http::Client;
+
+// Paragraphs are wrapped to this many columns in the terminal
+const WIDTH: usize = 80;
+
+lazy_static! {
+    static ref ARTICLE_PATTERN: Regex = Regex::new("(?s)<article[^>]*>(.*?)</article>").unwrap();
+    static ref TOKEN_PATTERN: Regex = Regex::new("(?s)<(/?)(\\w+)([^>]*)>|([^<]+)").unwrap();
+    static ref HREF_PATTERN: Regex = Regex::new("href=\"([^\"]*)\"").unwrap();
+    static ref ENTITY_PATTERN: Regex = Regex::new("&(#\\d+|#x[0-9a-fA-F]+|\\w+);").unwrap();
+    static ref WHITESPACE_PATTERN: Regex = Regex::new("\\s+").unwrap();
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Format {
+    /// Plain text wrapped to 80 columns, with examples indented
+    Text,
+    /// Markdown, with examples in fenced code blocks
+    Markdown,
+}
+
+// Description of a puzzle, made of the articles of both parts once the second one is unlocked
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Page {
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Block {
+    Heading(Vec<Span>),
+    Paragraph(Vec<Span>),
+    List(Vec<Vec<Span>>),
+    // Preformatted text, which is how puzzles show their examples
+    Code(String),
+}
+
+// Run of text sharing the same style, such as <code><em>24000</em></code>
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Span {
+    pub text: String,
+    pub code: bool,
+    pub emphasis: bool,
+    pub link: Option<String>,
+}
+
+// Downloads the puzzle page with the session, so that it includes the second part once it is
+// unlocked, and stores it in the cache
+pub async fn fetch(
+    client: &Client,
+    base_url: &str,
+    cache: &Cache,
+    year: Year,
+    day: Day,
+) -> anyhow::Result<String> {
+    crate::release::check_released(year, day)?;
+
+    let url = url(base_url, year, day);
+    let page = client
+        .get(&url)
+        .await
+        .with_context(|| format!("Could not download the puzzle of {} {}", year, day))?;
+
+    cache.write_page(year, day, &page)?;
+    Ok(page)
+}
+
+// Pages show the second part once the first one is solved, so a cached page with a single part is
+// only worth downloading again after a correct answer to the first part has been submitted
+pub fn is_stale(html: &str, part_a_solved: bool) -> bool {
+    part_a_solved && ARTICLE_PATTERN.find_iter(html).count() < 2
+}
+
+pub fn url(base_url: &str, year: Year, day: Day) -> String {
+    format!("{}/{}/day/{}", base_url, year, u8::from(day))
+}
+
+impl Page {
+    // Reads the articles of the page downloaded from url
+    pub fn parse(html: &str, url: &str) -> anyhow::Result<Self> {
+        let mut parser = Parser {
+            url: url.to_string(),
+            ..Parser::default()
+        };
+
+        for article in ARTICLE_PATTERN.captures_iter(html) {
+            parser.parse(&article[1]);
+        }
+
+        if parser.blocks.is_empty() {
+            bail!("The page does not contain a puzzle description");
+        }
+
+        Ok(Page {
+            blocks: parser.blocks,
+        })
+    }
+
+    // Example inputs, which are the preformatted blocks introduced by a paragraph mentioning an
+    // example, such as "For example:" or "Here's a larger example:". Other blocks tend to show
+    // intermediate states. Falls back to the first block when none are introduced that way.
+    pub fn examples(&self) -> Vec<&str> {
+        let mut examples = Vec::<&str>::new();
+        let mut introduced = false;
+
+        for block in &self.blocks {
+            match block {
+                Block::Code(code) if introduced && !examples.contains(&code.as_str()) => {
+                    examples.push(code)
+                }
+                Block::Paragraph(spans) => {
+                    introduced = spans
+                        .iter()
+                        .any(|span| span.text.to_lowercase().contains("example"))
+                }
+                _ => {}
+            }
+        }
+
+        if examples.is_empty() {
+            examples.extend(self.blocks.iter().find_map(|block| match block {
+                Block::Code(code) => Some(code.as_str()),
+                _ => None,
+            }));
+        }
+
+        examples
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|block| match format {
+                Format::Text => render_text(block),
+                Format::Markdown => render_markdown(block),
+            })
+            .collect::<Vec<_>>();
+
+        blocks.join("\n")
+    }
+}
+
+fn render_text(block: &Block) -> String {
+    let plain = |spans: &[Span]| {
+        spans
+            .iter()
+            .map(|span| span.text.as_str())
+            .collect::<String>()
+    };
+
+    match block {
+        Block::Heading(spans) => format!("{}\n", plain(spans).trim()),
+        Block::Paragraph(spans) => wrap(&plain(spans), "", ""),
+        Block::List(items) => items
+            .iter()
+            .map(|item| wrap(&plain(item), "  - ", "    "))
+            .collect(),
+        Block::Code(code) => code
+            .lines()
+            .map(|line| match line {
+                "" => "\n".to_string(),
+                _ => format!("    {}\n", line),
+            })
+            .collect(),
+    }
+}
+
+fn render_markdown(block: &Block) -> String {
+    let inline = |spans: &[Span]| spans.iter().map(markdown_span).collect::<String>();
+
+    match block {
+        Block::Heading(spans) => format!("## {}\n", inline(spans).trim()),
+        Block::Paragraph(spans) => format!("{}\n", inline(spans).trim()),
+        Block::List(items) => items
+            .iter()
+            .map(|item| format!("- {}\n", inline(item).trim()))
+            .collect(),
+        Block::Code(code) => format!("```\n{}\n```\n", code.trim_end_matches('\n')),
+    }
+}
+
+fn markdown_span(span: &Span) -> String {
+    let text = span.text.as_str();
+
+    // Surrounding whitespace has to stay outside of the markers
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len();
+
+    if start >= end {
+        return text.to_string();
+    }
+
+    let mut styled = text[start..end].to_string();
+
+    if span.code {
+        styled = format!("`{}`", styled);
+    }
+
+    if span.emphasis {
+        styled = format!("**{}**", styled);
+    }
+
+    if let Some(link) = &span.link {
+        styled = format!("[{}]({})", styled, link);
+    }
+
+    format!("{}{}{}", &text[..start], styled, &text[end..])
+}
+
+// Breaks the text into lines of at most WIDTH columns, unless a single word is longer
+fn wrap(text: &str, first_indent: &str, indent: &str) -> String {
+    let mut lines = Vec::new();
+    let mut line = first_indent.to_string();
+    let mut empty = true;
+
+    for word in text.split_whitespace() {
+        if !empty && line.chars().count() + 1 + word.chars().count() > WIDTH {
+            lines.push(line);
+            line = indent.to_string();
+            empty = true;
+        }
+
+        if !empty {
+            line.push(' ');
+        }
+
+        line.push_str(word);
+        empty = false;
+    }
+
+    lines.push(line);
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+// Walks through the tags of an article, which only uses a handful of them
+#[derive(Default)]
+struct Parser {
+    // Address of the page, which links are relative to
+    url: String,
+    blocks: Vec<Block>,
+    spans: Option<Vec<Span>>,
+    items: Option<Vec<Vec<Span>>>,
+    code: Option<String>,
+    // Nesting of the inline tags, which can appear within each other
+    inline_code: usize,
+    emphasis: usize,
+    link: Option<String>,
+}
+
+impl Parser {
+    fn parse(&mut self, html: &str) {
+        for token in TOKEN_PATTERN.captures_iter(html) {
+            if let Some(text) = token.get(4) {
+                self.text(&decode_entities(text.as_str()));
+                continue;
+            }
+
+            let closing = &token[1] == "/";
+            let attributes = &token[3];
+
+            match (&token[2], closing) {
+                ("pre", false) => self.code = Some(String::new()),
+                ("pre", true) => {
+                    if let Some(code) = self.code.take() {
+                        self.blocks.push(Block::Code(code));
+                    }
+                }
+                // Everything within an example is shown as it is
+                _ if self.code.is_some() => {}
+                ("h2" | "p", false) => self.spans = Some(Vec::new()),
+                ("h2", true) => {
+                    if let Some(spans) = self.spans.take() {
+                        self.blocks.push(Block::Heading(spans));
+                    }
+                }
+                ("p", true) => {
+                    if let Some(spans) = self.spans.take() {
+                        self.blocks.push(Block::Paragraph(spans));
+                    }
+                }
+                ("ul", false) => self.items = Some(Vec::new()),
+                ("ul", true) => {
+                    if let Some(items) = self.items.take() {
+                        self.blocks.push(Block::List(items));
+                    }
+                }
+                ("li", false) => self.spans = Some(Vec::new()),
+                ("li", true) => {
+                    if let (Some(items), Some(spans)) = (&mut self.items, self.spans.take()) {
+                        items.push(spans);
+                    }
+                }
+                ("code", false) => self.inline_code += 1,
+                ("code", true) => self.inline_code = self.inline_code.saturating_sub(1),
+                ("em", false) => self.emphasis += 1,
+                ("em", true) => self.emphasis = self.emphasis.saturating_sub(1),
+                ("a", false) => {
+                    self.link = HREF_PATTERN
+                        .captures(attributes)
+                        .map(|href| resolve(&self.url, &decode_entities(&href[1])))
+                }
+                ("a", true) => self.link = None,
+                _ => {}
+            }
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some(code) = &mut self.code {
+            code.push_str(text);
+            return;
+        }
+
+        let spans = match &mut self.spans {
+            Some(spans) => spans,
+            None => return,
+        };
+
+        let text = WHITESPACE_PATTERN.replace_all(text, " ");
+        let span = Span {
+            text: text.to_string(),
+            code: self.inline_code > 0,
+            emphasis: self.emphasis > 0,
+            link: self.link.clone(),
+        };
+
+        match spans.last_mut() {
+            Some(last)
+                if last.code == span.code
+                    && last.emphasis == span.emphasis
+                    && last.link == span.link =>
+            {
+                last.text.push_str(&span.text)
+            }
+            _ => spans.push(span),
+        }
+    }
+}
+
+// Links are either absolute, relative to the site, such as /2022/day/1/input, or relative to the
+// page, such as 1 for the first day or #part2
+fn resolve(url: &str, href: &str) -> String {
+    if href.contains("://") {
+        return href.to_string();
+    }
+
+    let origin = url
+        .find("://")
+        .and_then(|scheme| url[scheme + 3..].find('/').map(|path| scheme + 3 + path))
+        .map_or(url, |path| &url[..path]);
+
+    if href.starts_with('/') {
+        format!("{}{}", origin, href)
+    } else if href.starts_with('#') {
+        format!("{}{}", url, href)
+    } else {
+        let directory = url.rfind('/').map_or(url, |slash| &url[..slash]);
+        format!("{}/{}", directory, href)
+    }
+}
+
+// Replaces the entities which appear in puzzles, such as &lt; and &#39;
+pub fn decode_entities(text: &str) -> String {
+    ENTITY_PATTERN
+        .replace_all(text, |captures: &regex::Captures| {
+            let entity = &captures[1];
+
+            let char = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => match entity.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                    None => entity
+                        .strip_prefix('#')
+                        .and_then(|decimal| decimal.parse().ok())
+                        .and_then(char::from_u32),
+                },
+            };
+
+            match char {
+                Some(char) => char.to_string(),
+                None => captures[0].to_string(),
+            }
+        })
+        .into_owned()
+}
@@ -0,0 +1,127 @@
+use std::io::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::bail;
+
+use crate::challenge::{Day, Year};
+
+// Puzzles unlock at midnight EST (UTC-5), the site does not follow daylight saving time
+const RELEASE_HOUR_UTC: u64 = 5;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Moment the puzzle of the given day of December unlocks
+pub fn release_time(year: Year, day: Day) -> SystemTime {
+    let days = days_from_civil(u16::from(year).into(), 12, day.into());
+    let seconds = days as u64 * SECONDS_PER_DAY + RELEASE_HOUR_UTC * 60 * 60;
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+// Number of days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year } as i64;
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// Date of the given number of days since 1970-01-01, the inverse of days_from_civil
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month as u8, day as u8)
+}
+
+// Formats seconds since the epoch as a UTC date and time, such as 2022-12-01 05:12:33
+pub fn format_timestamp(timestamp: u64) -> String {
+    let (year, month, day) = civil_from_days((timestamp / SECONDS_PER_DAY) as i64);
+    let seconds = timestamp % SECONDS_PER_DAY;
+
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+// Fails with the remaining time when the puzzle is still locked, so that we never ask the site
+// for something it cannot have yet
+pub fn check_released(year: Year, day: Day) -> anyhow::Result<()> {
+    if let Some(remaining) = day.time_until_release(year) {
+        bail!(
+            "{} {} unlocks in {}, pass --wait to wait for it",
+            year,
+            day,
+            format_countdown(remaining)
+        );
+    }
+
+    Ok(())
+}
+
+// Counts down on stderr until the puzzle unlocks
+pub async fn wait(year: Year, day: Day) {
+    let mut stderr = std::io::stderr();
+
+    while let Some(remaining) = day.time_until_release(year) {
+        let _ = write!(
+            stderr,
+            "\r{} {} unlocks in {} ",
+            year,
+            day,
+            format_countdown(remaining)
+        );
+        let _ = stderr.flush();
+
+        // Sleeping until the next full second keeps the countdown ticking evenly
+        let fraction = Duration::from_nanos(remaining.subsec_nanos() as u64);
+        let step = if fraction.is_zero() {
+            Duration::from_secs(1)
+        } else {
+            fraction
+        };
+
+        tokio::time::sleep(step.min(remaining)).await;
+    }
+
+    let _ = writeln!(stderr, "\r{} {} is unlocked{}", year, day, " ".repeat(16));
+}
+
+// Formats durations such as 1d 02:03:04, rounding up so that zero is only shown once unlocked
+pub fn format_countdown(duration: Duration) -> String {
+    let mut seconds = duration.as_secs();
+
+    if duration.subsec_nanos() > 0 {
+        seconds += 1;
+    }
+
+    let days = seconds / SECONDS_PER_DAY;
+    let hours = seconds % SECONDS_PER_DAY / 3600;
+    let minutes = seconds % 3600 / 60;
+    let seconds = seconds % 60;
+
+    if days > 0 {
+        format!("{}d {:02}:{:02}:{:02}", days, hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    }
+}
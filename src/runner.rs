@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::bail;
+use tokio::sync::Semaphore;
+
+use crate::answers::{Answers, Verification};
+use crate::challenge::{self, Challenge, Day, Parameters, Report, Year};
+use crate::input::InputSource;
+
+// Solves the challenges with at most jobs inputs being read or solutions running at the same
+// time, while still passing the reports to on_report in challenge order as soon as they are
+// available
+pub async fn run(
+    challenges: &[Challenge],
+    source: InputSource,
+    parameters: Parameters,
+    jobs: usize,
+    limit: Option<Duration>,
+    mut on_report: impl FnMut(&Report) -> anyhow::Result<()>,
+) -> anyhow::Result<Vec<Report>> {
+    challenge::solutions()?;
+    let source = Arc::new(source);
+    let parameters = Arc::new(parameters);
+    let semaphore = Arc::new(Semaphore::new(jobs));
+
+    // Both parts of a day share its input, which is only read once
+    let mut days = BTreeMap::<(Year, Day), Vec<Challenge>>::new();
+
+    for challenge in challenges {
+        days.entry((challenge.year(), challenge.day()))
+            .or_default()
+            .push(*challenge);
+    }
+
+    let tasks = days
+        .into_iter()
+        .map(|((year, day), challenges)| {
+            tokio::spawn(solve_day(
+                year,
+                day,
+                challenges,
+                source.clone(),
+                parameters.clone(),
+                semaphore.clone(),
+                limit,
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    let mut reports = Vec::with_capacity(challenges.len());
+
+    for task in tasks {
+        for report in task.await?? {
+            on_report(&report)?;
+            reports.push(report);
+        }
+    }
+
+    Ok(reports)
+}
+
+async fn solve_day(
+    year: Year,
+    day: Day,
+    challenges: Vec<Challenge>,
+    source: Arc<InputSource>,
+    parameters: Arc<Parameters>,
+    semaphore: Arc<Semaphore>,
+    limit: Option<Duration>,
+) -> anyhow::Result<Vec<Report>> {
+    let input = {
+        let _permit = semaphore.acquire().await?;
+        source.read(year, day).await
+    };
+
+    // Both parts solve the same model, which is only parsed once
+    let parsed = match input {
+        Ok(input) => {
+            let _permit = semaphore.acquire().await?;
+            let input = Arc::<str>::from(input);
+            challenge::solutions()?
+                .parse_in_thread(year, day, input, parameters.clone(), limit)
+                .await
+        }
+        Err(error) => Err(error),
+    };
+
+    let parsed = match parsed {
+        Ok(parsed) => Arc::new(parsed),
+        Err(error) => {
+            return Ok(challenges
+                .into_iter()
+                .map(|challenge| Report::failed(challenge, &error))
+                .collect())
+        }
+    };
+
+    let tasks = challenges
+        .into_iter()
+        .map(|challenge| {
+            let parsed = parsed.clone();
+            let parameters = parameters.clone();
+            let semaphore = semaphore.clone();
+
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await?;
+                let report =
+                    challenge::solutions()?.solve_in_thread(challenge, parsed, parameters, limit);
+                anyhow::Ok(report.await)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut reports = Vec::with_capacity(tasks.len());
+
+    for task in tasks {
+        reports.push(task.await??);
+    }
+
+    Ok(reports)
+}
+
+// Lists every challenge with the time spent parsing its input and solving it, followed by the
+// totals and the time the whole run took, which is shorter when running several jobs. Both parts of
+// a day share the parsed input, so its parse time only counts once towards the total. Days solved by
+// plain functions parse while solving and have no parse time of their own.
+pub fn print_summary(
+    out: &mut impl Write,
+    reports: &[Report],
+    elapsed: Duration,
+) -> std::io::Result<()> {
+    let mut rows = vec![[
+        "Challenge".to_string(),
+        "Answer".to_string(),
+        "Parse".to_string(),
+        "Solve".to_string(),
+    ]];
+
+    for report in reports {
+        let answer = match report.answer() {
+            Some(answer) if answer.is_grid() => "(picture)".to_string(),
+            Some(answer) => answer.to_string(),
+            None => "error".to_string(),
+        };
+
+        rows.push([
+            report.challenge.to_string(),
+            answer,
+            report
+                .parse
+                .map_or("-".to_string(), |parse| format!("{:?}", parse)),
+            format!("{:?}", report.duration),
+        ]);
+    }
+
+    let parse = reports
+        .iter()
+        .filter_map(|report| {
+            let key = (report.challenge.year(), report.challenge.day());
+            Some((key, report.parse?))
+        })
+        .collect::<BTreeMap<_, _>>();
+    let parse_total = parse.values().sum::<Duration>();
+    let solve_total = reports
+        .iter()
+        .map(|report| report.duration)
+        .sum::<Duration>();
+
+    let totals = [
+        (format!("Parse ({} inputs)", parse.len()), parse_total),
+        (format!("Solve ({} challenges)", reports.len()), solve_total),
+        ("Total".to_string(), parse_total + solve_total),
+        ("Elapsed".to_string(), elapsed),
+    ]
+    .map(|(name, duration)| {
+        [
+            name,
+            String::new(),
+            String::new(),
+            format!("{:?}", duration),
+        ]
+    });
+
+    let mut widths = [0; 4];
+
+    for row in rows.iter().chain(&totals) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let [name_width, answer_width, parse_width, solve_width] = widths;
+    let separator = "-".repeat(widths.iter().sum::<usize>() + 6);
+
+    let write_row = |out: &mut dyn Write, row: &[String; 4]| {
+        writeln!(
+            out,
+            "{:name_width$}  {:answer_width$}  {:>parse_width$}  {:>solve_width$}",
+            row[0], row[1], row[2], row[3]
+        )
+    };
+
+    writeln!(out)?;
+    write_row(out, &rows[0])?;
+    writeln!(out, "{}", separator)?;
+
+    for row in &rows[1..] {
+        write_row(out, row)?;
+    }
+
+    writeln!(out, "{}", separator)?;
+
+    for row in &totals {
+        write_row(out, row)?;
+    }
+
+    Ok(())
+}
+
+// Counts the challenges which were solved and lists the ones which failed or panicked, whose errors
+// may have scrolled by long ago
+pub fn print_results(
+    out: &mut impl Write,
+    total: usize,
+    failed: &[Challenge],
+) -> std::io::Result<()> {
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{} passed, {} failed",
+        total - failed.len(),
+        failed.len()
+    )?;
+
+    for challenge in failed {
+        writeln!(out, "  {}", challenge)?;
+    }
+
+    Ok(())
+}
+
+pub fn verify(out: &mut impl Write, answers: &Answers, reports: &[Report]) -> anyhow::Result<()> {
+    let mut failures = 0;
+
+    for report in reports {
+        let challenge = &report.challenge;
+
+        let answer = match report.answer() {
+            Some(answer) => answer,
+            None => {
+                writeln!(out, "{}: fail (no answer)", challenge)?;
+                failures += 1;
+                continue;
+            }
+        };
+
+        match answers.verify(challenge, answer) {
+            Verification::Pass => writeln!(out, "{}: pass", challenge)?,
+            Verification::Fail(expected) => {
+                writeln!(
+                    out,
+                    "{}: fail (expected {}, got {})",
+                    challenge, expected, answer
+                )?;
+                failures += 1;
+            }
+            Verification::Unknown => writeln!(out, "{}: unknown", challenge)?,
+        }
+    }
+
+    if failures > 0 {
+        bail!("{} of {} answers did not match", failures, reports.len());
+    }
+
+    Ok(())
+}
+
+// Only successful answers are recorded, failed challenges keep their previous entry
+pub fn record(
+    out: &mut impl Write,
+    mut answers: Answers,
+    reports: &[Report],
+) -> anyhow::Result<()> {
+    let mut count = 0;
+
+    for report in reports {
+        if let Some(answer) = report.answer() {
+            answers.insert(&report.challenge, answer.to_string());
+            count += 1;
+        }
+    }
+
+    answers.save()?;
+    writeln!(out, "Recorded {} answers", count)?;
+    Ok(())
+}
@@ -0,0 +1,301 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::answers::Answers;
+use crate::challenge::{Challenge, Day, Part, Year};
+use crate::http::Client;
+
+const MODULE_TEMPLATE: &str = "use {registry}::register;
+use anyhow::bail;
+
+register! {
+{year}    day: {day},
+    title: {title},
+{parts}}
+
+{functions}";
+
+// Stubs fail until the part is solved, so that a wrong answer is never recorded
+const PART_TEMPLATE: &str = "pub fn {name}(input: &[&str]) -> anyhow::Result<u64> {
+    bail!(\"{part} is not solved yet, the input has {} lines\", input.len())
+}
+";
+
+lazy_static! {
+    static ref TITLE_PATTERN: Regex = Regex::new("<h2>--- Day \\d+: (.*?) ---</h2>").unwrap();
+    static ref MODULE_PATTERN: Regex = Regex::new("(?m)^(?:pub )?mod (\\w+);\n").unwrap();
+}
+
+// Fetches the puzzle page and reads the title out of its heading, such as
+// <h2>--- Day 1: Calorie Counting ---</h2>
+pub async fn fetch_title(
+    client: &Client,
+    base_url: &str,
+    year: Year,
+    day: Day,
+) -> anyhow::Result<String> {
+    crate::release::check_released(year, day)?;
+
+    let url = crate::page::url(base_url, year, day);
+    let page = client
+        .get(&url)
+        .await
+        .with_context(|| format!("Could not fetch the title of {} {}", year, day))?;
+
+    parse_title(&page)
+}
+
+pub fn parse_title(html: &str) -> anyhow::Result<String> {
+    let title = TITLE_PATTERN
+        .captures(html)
+        .and_then(|captures| captures.get(1))
+        .context("Could not find the title on the puzzle page")?;
+
+    Ok(crate::page::decode_entities(title.as_str()))
+}
+
+// Fails when the day already has a module in the repository at root, which create never
+// overwrites, otherwise returns the path the module would be written to
+pub fn check_new(root: &Path, year: Year, day: Day) -> anyhow::Result<PathBuf> {
+    let directory = year_directory(&root.join("src").join("challenge"), year);
+    let module = directory.join(format!("day_{:02}.rs", u8::from(day)));
+
+    if module.exists() {
+        bail!("{} already exists", module.display());
+    }
+
+    Ok(module)
+}
+
+// Sets up a new day in the repository at root: its module with stubs for both parts, its entry in
+// the list of modules, an example fixture to fill in and empty entries in the answers file. Days of
+// other years than the default one go into a module of their own, such as src/challenge/y2023,
+// with their fixtures in tests/examples/y2023. The module is never overwritten, everything else is
+// only added when it is missing. Returns the files which were written.
+pub fn create(
+    root: &Path,
+    answers: &mut Answers,
+    year: Year,
+    day: Day,
+    title: &str,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let module = check_new(root, year, day)?;
+    let challenges = root.join("src").join("challenge");
+    let directory = year_directory(&challenges, year);
+    let mut written = Vec::new();
+    let nested = year != Year::DEFAULT;
+
+    if nested {
+        let name = format!("y{}", year);
+        written.extend(register_module(&challenges.join("mod.rs"), &name, false)?);
+
+        std::fs::create_dir_all(&directory)
+            .with_context(|| format!("Could not create {}", directory.display()))?;
+    }
+
+    let name = format!("day_{:02}", u8::from(day));
+    written.extend(register_module(&directory.join("mod.rs"), &name, nested)?);
+
+    std::fs::write(&module, module_template(year, day, title))
+        .with_context(|| format!("Could not write {}", module.display()))?;
+    written.push(module);
+
+    let examples = year_directory(&root.join("tests").join("examples"), year);
+    let fixture = examples.join(format!("day_{:02}.txt", u8::from(day)));
+
+    if !fixture.exists() {
+        std::fs::create_dir_all(&examples)
+            .with_context(|| format!("Could not create {}", examples.display()))?;
+        std::fs::write(&fixture, fixture_template(year, day))
+            .with_context(|| format!("Could not write {}", fixture.display()))?;
+        written.push(fixture);
+    }
+
+    for part in parts(day) {
+        answers.reserve(&Challenge::new(year, day, *part));
+    }
+
+    Ok(written)
+}
+
+// Stores the examples of a puzzle as fixtures, named like day_06.txt and day_06_2.txt, with the
+// answers left to fill in. Fixtures which already have an input are kept, while the ones created
+// along with the module receive the example. Returns the files which were written.
+pub fn save_examples(
+    root: &Path,
+    year: Year,
+    day: Day,
+    examples: &[&str],
+) -> anyhow::Result<Vec<PathBuf>> {
+    let directory = year_directory(&root.join("tests").join("examples"), year);
+    let mut written = Vec::new();
+
+    std::fs::create_dir_all(&directory)
+        .with_context(|| format!("Could not create {}", directory.display()))?;
+
+    for (index, example) in examples.iter().enumerate() {
+        let name = match index {
+            0 => format!("day_{:02}.txt", u8::from(day)),
+            _ => format!("day_{:02}_{}.txt", u8::from(day), index + 1),
+        };
+
+        let path = directory.join(name);
+
+        let header = match std::fs::read_to_string(&path) {
+            Ok(content) => match content.split_once("---\n") {
+                Some((header, input)) if input.trim().is_empty() => format!("{}---\n", header),
+                _ => continue,
+            },
+            Err(error) if error.kind() == ErrorKind::NotFound => fixture_template(year, day),
+            Err(error) => {
+                return Err(error).with_context(|| format!("Could not read {}", path.display()))
+            }
+        };
+
+        std::fs::write(&path, format!("{}{}", header, example))
+            .with_context(|| format!("Could not write {}", path.display()))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+// Days of the default year live right in the directory, other years in a directory such as y2023
+fn year_directory(directory: &Path, year: Year) -> PathBuf {
+    if year == Year::DEFAULT {
+        directory.to_path_buf()
+    } else {
+        directory.join(format!("y{}", year))
+    }
+}
+
+// Adds the module to the list in the mod.rs file, which is created when missing is allowed. Returns
+// the file when it was written.
+fn register_module(registry: &Path, name: &str, missing: bool) -> anyhow::Result<Option<PathBuf>> {
+    let content = match std::fs::read_to_string(registry) {
+        Ok(content) => content,
+        Err(error) if missing && error.kind() == ErrorKind::NotFound => String::new(),
+        Err(error) => {
+            return Err(error).with_context(|| {
+                format!(
+                    "Could not read {}, is this the repository?",
+                    registry.display()
+                )
+            })
+        }
+    };
+
+    let content = match add_module(&content, name)
+        .with_context(|| format!("Could not add {} to {}", name, registry.display()))?
+    {
+        Some(content) => content,
+        None => return Ok(None),
+    };
+
+    std::fs::write(registry, content)
+        .with_context(|| format!("Could not write {}", registry.display()))?;
+
+    Ok(Some(registry.to_path_buf()))
+}
+
+// The second star of the last day is given for all the others
+fn parts(day: Day) -> &'static [Part] {
+    match day {
+        Day::Day25 => &[Part::PartA],
+        _ => &[Part::PartA, Part::PartB],
+    }
+}
+
+// Inserts "pub mod name;" into the list of modules, which rustfmt keeps sorted by name. Returns
+// None when the module is already there.
+fn add_module(content: &str, name: &str) -> anyhow::Result<Option<String>> {
+    let mut first = None;
+    let mut after = None;
+
+    for captures in MODULE_PATTERN.captures_iter(content) {
+        let existing = &captures[1];
+        let line = captures.get(0).context("Match is missing")?;
+
+        if existing == name {
+            return Ok(None);
+        }
+
+        first.get_or_insert(line.start());
+
+        if existing < name {
+            after = Some(line.end());
+        }
+    }
+
+    // A new file starts the list
+    let position = after
+        .or(first)
+        .or_else(|| content.is_empty().then_some(0))
+        .context("Could not find the list of modules")?;
+    let line = format!("pub mod {};\n", name);
+
+    Ok(Some(format!(
+        "{}{}{}",
+        &content[..position],
+        line,
+        &content[position..]
+    )))
+}
+
+fn module_template(year: Year, day: Day, title: &str) -> String {
+    let registered = parts(day)
+        .iter()
+        .map(|part| format!("    {}: lines({}),\n", part_name(*part), part_name(*part)))
+        .collect::<String>();
+
+    let functions = parts(day)
+        .iter()
+        .map(|part| {
+            PART_TEMPLATE
+                .replace("{name}", part_name(*part))
+                .replace("{part}", &part.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Modules of other years are nested one level deeper
+    let (registry, year) = if year == Year::DEFAULT {
+        ("super", String::new())
+    } else {
+        ("crate::challenge", format!("    year: {},\n", year))
+    };
+
+    MODULE_TEMPLATE
+        .replace("{registry}", registry)
+        .replace("{year}", &year)
+        .replace("{day}", &format!("Day{:02}", u8::from(day)))
+        .replace("{title}", &format!("{:?}", title))
+        .replace("{parts}", &registered)
+        .replace("{functions}", &functions)
+}
+
+fn fixture_template(year: Year, day: Day) -> String {
+    let answers = parts(day)
+        .iter()
+        .map(|part| format!("{}:\n", part_name(*part)))
+        .collect::<String>();
+
+    format!(
+        "# {}/{}/day/{}\n{}---\n",
+        crate::http::BASE_URL,
+        year,
+        u8::from(day),
+        answers
+    )
+}
+
+fn part_name(part: Part) -> &'static str {
+    match part {
+        Part::PartA => "part_a",
+        Part::PartB => "part_b",
+    }
+}
@@ -0,0 +1,181 @@
+use std::fmt::{Debug, Display};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::challenge::Year;
+use crate::http::{Client, SessionExpired};
+
+lazy_static! {
+    static ref USER_PATTERN: Regex = Regex::new("<div class=\"user\">([^<]*)").unwrap();
+}
+
+// Value of the session cookie. It is never printed, Debug only shows that it is there.
+#[derive(Clone)]
+pub struct Session(String);
+
+impl Session {
+    pub fn new(value: &str) -> anyhow::Result<Self> {
+        let value = value.trim();
+
+        if value.is_empty() {
+            bail!("The session is empty");
+        }
+
+        // Checked without echoing the value, which could be a pasted secret
+        if !value.chars().all(|char| char.is_ascii_alphanumeric()) {
+            bail!("The session contains unexpected characters, expecting only letters and digits");
+        }
+
+        Ok(Session(value.to_string()))
+    }
+
+    pub fn cookie(&self) -> String {
+        format!("session={}", self.0)
+    }
+}
+
+impl Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Session(<redacted>)")
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Source {
+    Environment,
+    Flag,
+    ConfigFile(PathBuf),
+    TokenFile(PathBuf),
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Environment => write!(f, "the SESSION environment variable"),
+            Source::Flag => write!(f, "the --session flag"),
+            Source::ConfigFile(path) => write!(f, "{}", path.display()),
+            Source::TokenFile(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+// Places to look for the session, in order: the SESSION environment variable, the --session
+// flag, the config file (~/.config/aoc/session) and finally a token file passed by path
+#[derive(Clone, Default)]
+pub struct Credentials {
+    pub environment: Option<String>,
+    pub flag: Option<String>,
+    pub config_file: Option<PathBuf>,
+    pub token_file: Option<PathBuf>,
+}
+
+impl Credentials {
+    pub fn from_env() -> Self {
+        let config_directory = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(directory) => Some(PathBuf::from(directory)),
+            None => std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")),
+        };
+
+        Credentials {
+            environment: std::env::var("SESSION").ok(),
+            flag: None,
+            config_file: config_directory.map(|directory| directory.join("aoc").join("session")),
+            token_file: None,
+        }
+    }
+
+    pub fn resolve(&self) -> anyhow::Result<(Session, Source)> {
+        if let Some(value) = self
+            .environment
+            .as_deref()
+            .filter(|value| !value.is_empty())
+        {
+            let session = Session::new(value).context("SESSION is not a valid session")?;
+            return Ok((session, Source::Environment));
+        }
+
+        if let Some(value) = &self.flag {
+            let session = Session::new(value).context("--session is not a valid session")?;
+            return Ok((session, Source::Flag));
+        }
+
+        if let Some(path) = self.config_file.as_deref().filter(|path| path.exists()) {
+            check_permissions(path)?;
+            let session = read_session(path)?;
+            return Ok((session, Source::ConfigFile(path.to_path_buf())));
+        }
+
+        if let Some(path) = &self.token_file {
+            let session = read_session(path)?;
+            return Ok((session, Source::TokenFile(path.clone())));
+        }
+
+        match &self.config_file {
+            Some(path) => bail!(
+                "No session found, set SESSION, pass --session or --session-file, or save it to {}",
+                path.display()
+            ),
+            None => bail!("No session found, set SESSION or pass --session or --session-file"),
+        }
+    }
+}
+
+impl Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redacted = |value: &Option<String>| value.as_ref().map(|_| "<redacted>");
+
+        f.debug_struct("Credentials")
+            .field("environment", &redacted(&self.environment))
+            .field("flag", &redacted(&self.flag))
+            .field("config_file", &self.config_file)
+            .field("token_file", &self.token_file)
+            .finish()
+    }
+}
+
+fn read_session(path: &Path) -> anyhow::Result<Session> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Could not read {}", path.display()))?;
+
+    Session::new(&content)
+        .with_context(|| format!("{} is not a valid session file", path.display()))
+}
+
+// The session grants full access to the account, so other users must not be able to read it
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let metadata =
+        std::fs::metadata(path).with_context(|| format!("Could not access {}", path.display()))?;
+
+    if metadata.permissions().mode() & 0o077 != 0 {
+        bail!(
+            "{} is accessible by other users, restrict it with chmod 600",
+            path.display()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_: &Path) -> anyhow::Result<()> {
+    Ok(())
+}
+
+// Fetches the event page of the year, which shows the name of the logged in user, to confirm that
+// the session still works
+pub async fn whoami(client: &Client, base_url: &str, year: Year) -> anyhow::Result<String> {
+    let page = client.get(&format!("{}/{}", base_url, year)).await?;
+
+    let user = USER_PATTERN
+        .captures(&page)
+        .and_then(|captures| captures.get(1))
+        .ok_or(SessionExpired)?;
+
+    Ok(user.as_str().trim().to_string())
+}
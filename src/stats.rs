@@ -0,0 +1,217 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::challenge::{Day, Part, Report, Year};
+use crate::leaderboard::Member;
+use crate::release::{format_countdown, format_timestamp};
+
+const PARTS: [Part; 2] = [Part::PartA, Part::PartB];
+
+// Progress of a single member over a whole event, along with how fast the solutions run
+pub struct Stats {
+    pub year: Year,
+    pub name: String,
+    pub days: Vec<DayStats>,
+}
+
+// Everything known about both parts of a day, indexed by part
+pub struct DayStats {
+    pub day: Day,
+    // Seconds since the epoch when the stars were collected
+    pub stars: [Option<u64>; 2],
+    // Time from the release of the puzzle to the stars
+    pub times: [Option<Duration>; 2],
+    // Time the solutions take to parse the input and solve the part
+    pub runtimes: [Option<Duration>; 2],
+}
+
+impl Stats {
+    // Combines the stars of the member with the reports of the solutions, failed ones are left
+    // without a runtime
+    pub fn new(year: Year, member: &Member, reports: &[Report]) -> Self {
+        let days = (1..=25)
+            .filter_map(|day| Day::try_from(day).ok())
+            .map(|day| {
+                let runtime = |part| {
+                    reports
+                        .iter()
+                        .find(|report| {
+                            report.challenge.year() == year
+                                && report.challenge.day() == day
+                                && report.challenge.part() == part
+                        })
+                        .filter(|report| report.answer().is_some())
+                        .map(|report| report.parse.unwrap_or_default() + report.duration)
+                };
+
+                DayStats {
+                    day,
+                    stars: PARTS.map(|part| member.star(day, part)),
+                    times: PARTS.map(|part| member.time_to_star(year, day, part)),
+                    runtimes: PARTS.map(runtime),
+                }
+            })
+            .collect();
+
+        Stats {
+            year,
+            name: member.name(),
+            days,
+        }
+    }
+
+    pub fn star_count(&self) -> usize {
+        self.days
+            .iter()
+            .flat_map(|day| day.stars)
+            .filter(Option::is_some)
+            .count()
+    }
+
+    // December laid out by week, starting on Monday, with the stars collected on every day
+    pub fn render_calendar(&self) -> String {
+        let mut calendar = format!(
+            "{}, {}: {} stars\n\n",
+            self.name,
+            self.year,
+            self.star_count()
+        );
+
+        calendar.push_str("Mon    Tue    Wed    Thu    Fri    Sat    Sun\n");
+
+        let offset = weekday(self.year, Day::Day01);
+        let mut cells = vec![String::new(); offset];
+
+        for day in &self.days {
+            let stars = day
+                .stars
+                .iter()
+                .map(|star| if star.is_some() { '*' } else { '.' })
+                .collect::<String>();
+
+            cells.push(format!("{:2} {}", u8::from(day.day), stars));
+        }
+
+        for week in cells.chunks(7) {
+            let line = week
+                .iter()
+                .map(|cell| format!("{:5}", cell))
+                .collect::<Vec<_>>()
+                .join("  ");
+
+            calendar.push_str(line.trim_end());
+            calendar.push('\n');
+        }
+
+        calendar
+    }
+
+    // Table of the days with a star or a runtime, showing how long after the release the stars
+    // came, how long part B took after part A and how long the solutions run
+    pub fn render_table(&self) -> String {
+        let mut rows = vec![[
+            "Day",
+            "Part A",
+            "Part B",
+            "A to B",
+            "Runtime A",
+            "Runtime B",
+        ]
+        .map(str::to_string)];
+
+        for day in &self.days {
+            if day.stars.iter().all(Option::is_none) && day.runtimes.iter().all(Option::is_none) {
+                continue;
+            }
+
+            let time = |time: Option<Duration>| time.map_or("-".to_string(), format_countdown);
+            let runtime = |runtime: Option<Duration>| {
+                runtime.map_or("-".to_string(), |runtime| format!("{:?}", runtime))
+            };
+
+            rows.push([
+                day.day.to_string(),
+                time(day.times[0]),
+                time(day.times[1]),
+                time(day.gap()),
+                runtime(day.runtimes[0]),
+                runtime(day.runtimes[1]),
+            ]);
+        }
+
+        let mut widths = [0; 6];
+
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        rows.iter()
+            .map(|row| {
+                let line = row
+                    .iter()
+                    .zip(widths)
+                    .enumerate()
+                    .map(|(index, (cell, width))| match index {
+                        0 => format!("{:width$}", cell),
+                        _ => format!("{:>width$}", cell),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("  ");
+
+                format!("{}\n", line)
+            })
+            .collect()
+    }
+
+    // One row for every day of the event. Stars are UTC timestamps, times are in seconds and
+    // runtimes in nanoseconds, like the other CSV output. Missing values are left empty.
+    pub fn to_csv(&self) -> String {
+        let mut csv = "day,part_a_star,part_b_star,part_a_seconds,part_b_seconds,gap_seconds,\
+                       part_a_runtime_ns,part_b_runtime_ns\n"
+            .to_string();
+
+        for day in &self.days {
+            let seconds = |time: Option<Duration>| time.map(|time| time.as_secs().to_string());
+
+            let fields = [
+                Some(u8::from(day.day).to_string()),
+                day.stars[0].map(format_timestamp),
+                day.stars[1].map(format_timestamp),
+                seconds(day.times[0]),
+                seconds(day.times[1]),
+                seconds(day.gap()),
+                day.runtimes[0].map(|runtime| runtime.as_nanos().to_string()),
+                day.runtimes[1].map(|runtime| runtime.as_nanos().to_string()),
+            ];
+
+            let row = fields.map(Option::unwrap_or_default).join(",");
+
+            csv.push_str(&row);
+            csv.push('\n');
+        }
+
+        csv
+    }
+}
+
+impl DayStats {
+    // Time between the two stars of the day
+    pub fn gap(&self) -> Option<Duration> {
+        let [a, b] = self.stars;
+        b?.checked_sub(a?).map(Duration::from_secs)
+    }
+}
+
+// Day of the week the puzzle is released on, counted from Monday. Puzzles unlock at 05:00 UTC, so
+// the UTC date is the date of the puzzle.
+fn weekday(year: Year, day: Day) -> usize {
+    let seconds = day
+        .release_time(year)
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    // 1970-01-01 was a Thursday
+    ((seconds / (24 * 60 * 60) + 3) % 7) as usize
+}
@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::challenge::{Challenge, Part};
+use crate::http::Client;
+
+lazy_static! {
+    static ref ARTICLE_PATTERN: Regex = Regex::new("(?s)<article>(.*?)</article>").unwrap();
+    static ref TAG_PATTERN: Regex = Regex::new("<[^>]*>").unwrap();
+    static ref WAIT_PATTERN: Regex =
+        Regex::new("You have (?:(\\d+)m )?(\\d+)s left to wait").unwrap();
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Outcome {
+    Correct,
+    Incorrect(Option<Hint>),
+    RateLimited(Duration),
+    AlreadySolved,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+// Posts the answer to base_url/YYYY/day/N/answer and interprets the returned page
+pub async fn submit(
+    client: &Client,
+    base_url: &str,
+    challenge: &Challenge,
+    answer: &str,
+) -> anyhow::Result<Outcome> {
+    crate::release::check_released(challenge.year(), challenge.day())?;
+
+    let url = format!(
+        "{}/{}/day/{}/answer",
+        base_url,
+        challenge.year(),
+        u8::from(challenge.day())
+    );
+
+    let level = match challenge.part() {
+        Part::PartA => "1",
+        Part::PartB => "2",
+    };
+
+    let response = client
+        .post(&url, &[("level", level), ("answer", answer)])
+        .await
+        .with_context(|| format!("Could not submit the answer for {}", challenge))?;
+
+    parse_response(&response)
+}
+
+pub fn parse_response(html: &str) -> anyhow::Result<Outcome> {
+    // The message is the only article on the page, everything else is navigation
+    let article = ARTICLE_PATTERN
+        .captures(html)
+        .and_then(|captures| captures.get(1))
+        .map_or(html, |article| article.as_str());
+    let text = TAG_PATTERN.replace_all(article, "");
+
+    if text.contains("That's the right answer") {
+        return Ok(Outcome::Correct);
+    }
+
+    if text.contains("That's not the right answer") {
+        let hint = if text.contains("your answer is too high") {
+            Some(Hint::TooHigh)
+        } else if text.contains("your answer is too low") {
+            Some(Hint::TooLow)
+        } else {
+            None
+        };
+
+        return Ok(Outcome::Incorrect(hint));
+    }
+
+    if text.contains("You gave an answer too recently") {
+        let captures = WAIT_PATTERN
+            .captures(&text)
+            .with_context(|| format!("Could not find the wait time in: {}", text.trim()))?;
+
+        let minutes = match captures.get(1) {
+            Some(minutes) => minutes.as_str().parse::<u64>()?,
+            None => 0,
+        };
+
+        let seconds = captures
+            .get(2)
+            .context("Seconds capture group is missing")?
+            .as_str()
+            .parse::<u64>()?;
+
+        return Ok(Outcome::RateLimited(Duration::from_secs(
+            minutes * 60 + seconds,
+        )));
+    }
+
+    if text.contains("You don't seem to be solving the right level") {
+        return Ok(Outcome::AlreadySolved);
+    }
+
+    bail!("Unexpected response: {}", text.trim())
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::Incorrect(None) => write!(f, "incorrect"),
+            Outcome::Incorrect(Some(Hint::TooHigh)) => write!(f, "incorrect, too high"),
+            Outcome::Incorrect(Some(Hint::TooLow)) => write!(f, "incorrect, too low"),
+            Outcome::RateLimited(wait) => write!(f, "rate limited, wait {:?}", wait),
+            Outcome::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+// Answers submitted so far, stored in a TOML file such as:
+//
+// [01a]
+// correct = "24000"
+// too_high = ["25000"]
+//
+// Used to avoid sending an answer which is already known to be wrong.
+pub struct Submissions {
+    path: PathBuf,
+    challenges: BTreeMap<String, History>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct History {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    correct: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    incorrect: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    too_high: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    too_low: Vec<String>,
+}
+
+impl Submissions {
+    pub fn load(path: PathBuf) -> anyhow::Result<Self> {
+        let challenges = match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content)
+                .with_context(|| format!("{} is not a valid submissions file", path.display()))?,
+            Err(error) if error.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => {
+                return Err(error).with_context(|| format!("Could not read {}", path.display()))
+            }
+        };
+
+        Ok(Submissions { path, challenges })
+    }
+
+    // Fails when the answer does not need to be sent, because the challenge is already solved or
+    // the previous outcomes rule the answer out
+    pub fn check(&self, challenge: &Challenge, answer: &str) -> anyhow::Result<()> {
+        let history = match self.challenges.get(&challenge.id()) {
+            Some(history) => history,
+            None => return Ok(()),
+        };
+
+        if let Some(correct) = &history.correct {
+            bail!("{} has already been solved with {}", challenge, correct);
+        }
+
+        let rejected = [&history.incorrect, &history.too_high, &history.too_low];
+
+        if rejected
+            .iter()
+            .flat_map(|answers| answers.iter())
+            .any(|rejected| rejected == answer)
+        {
+            bail!("{} has already been rejected for {}", answer, challenge);
+        }
+
+        if let Ok(value) = answer.parse::<i64>() {
+            let numbers = |answers: &[String]| {
+                answers
+                    .iter()
+                    .filter_map(|answer| answer.parse::<i64>().ok())
+                    .collect::<Vec<_>>()
+            };
+
+            if let Some(high) = numbers(&history.too_high).into_iter().min() {
+                if value >= high {
+                    bail!("{} is not lower than {}, which was too high", answer, high);
+                }
+            }
+
+            if let Some(low) = numbers(&history.too_low).into_iter().max() {
+                if value <= low {
+                    bail!("{} is not higher than {}, which was too low", answer, low);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn is_solved(&self, challenge: &Challenge) -> bool {
+        self.challenges
+            .get(&challenge.id())
+            .is_some_and(|history| history.correct.is_some())
+    }
+
+    pub fn record(&mut self, challenge: &Challenge, answer: &str, outcome: &Outcome) {
+        let history = self.challenges.entry(challenge.id()).or_default();
+        let answer = answer.to_string();
+
+        match outcome {
+            Outcome::Correct => history.correct = Some(answer),
+            Outcome::Incorrect(None) => history.incorrect.push(answer),
+            Outcome::Incorrect(Some(Hint::TooHigh)) => history.too_high.push(answer),
+            Outcome::Incorrect(Some(Hint::TooLow)) => history.too_low.push(answer),
+            // Neither says anything about the answer itself
+            Outcome::RateLimited(_) | Outcome::AlreadySolved => {}
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let content = toml::to_string(&self.challenges)?;
+        std::fs::write(&self.path, content)
+            .with_context(|| format!("Could not write {}", self.path.display()))
+    }
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use advent_of_code_2022::bench::{self, Statistics};
+use advent_of_code_2022::{solutions, Challenge, Parameters};
+
+const BLUEPRINT: &str = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. \
+                         Each obsidian robot costs 3 ore and 14 clay. \
+                         Each geode robot costs 2 ore and 7 obsidian.";
+
+fn measure(challenge: &str, input: &str) -> bench::Measurement {
+    let challenge = challenge.parse::<Challenge>().unwrap();
+    bench::measure(
+        solutions().unwrap(),
+        &challenge,
+        input,
+        &Parameters::new(),
+        1,
+        3,
+    )
+    .unwrap()
+}
+
+#[test]
+fn statistics_pick_the_middle_and_nearest_rank() {
+    let durations = [4, 1, 3, 2].map(Duration::from_millis).to_vec();
+    let statistics = Statistics::new(durations).unwrap();
+
+    assert_eq!(statistics.iterations, 4);
+    assert_eq!(statistics.min, Duration::from_millis(1));
+    assert_eq!(statistics.median, Duration::from_micros(2500));
+    assert_eq!(statistics.p95, Duration::from_millis(4));
+}
+
+#[test]
+fn puzzles_measure_parsing_on_its_own() {
+    let measurement = measure("19a", BLUEPRINT);
+    let parse = measurement.parse.unwrap();
+
+    assert_eq!(parse.iterations, 3);
+    assert_eq!(measurement.solve.iterations, 3);
+    assert!(measurement.total.min >= measurement.solve.min);
+}
+
+#[test]
+fn functions_only_measure_solving() {
+    let measurement = measure("21a", "root: pppw + sjmn\npppw: 2\nsjmn: 3");
+
+    assert!(measurement.parse.is_none());
+    assert_eq!(measurement.solve.iterations, 3);
+}
@@ -0,0 +1,342 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use advent_of_code_2022::challenge::{DaySolutions, Functions, Registration, Selector};
+use advent_of_code_2022::{
+    solutions, Answer, Cancellation, Challenge, Day, Parameters, Part, Solutions, Year,
+};
+
+fn year(year: u16) -> Year {
+    Year::new(year).unwrap()
+}
+
+#[test]
+fn challenges_default_to_2022() {
+    let challenge = "16a".parse::<Challenge>().unwrap();
+
+    assert_eq!(
+        challenge,
+        Challenge::new(Year::DEFAULT, Day::Day16, Part::PartA)
+    );
+    assert_eq!(challenge.id(), "16a");
+}
+
+#[test]
+fn parses_challenges_of_other_years() {
+    let challenge = "2021-16a".parse::<Challenge>().unwrap();
+
+    assert_eq!(
+        challenge,
+        Challenge::new(year(2021), Day::Day16, Part::PartA)
+    );
+    assert_eq!(challenge.id(), "2021-16a");
+    assert_eq!(challenge.title(), None);
+
+    let challenge = Challenge::parse("day 5b", year(2020)).unwrap();
+    assert_eq!(
+        challenge,
+        Challenge::new(year(2020), Day::Day05, Part::PartB)
+    );
+}
+
+#[test]
+fn rejects_years_before_the_first_event() {
+    assert!("2014-1a".parse::<Challenge>().is_err());
+    assert!("2014".parse::<Year>().is_err());
+}
+
+#[test]
+fn selectors_use_the_given_year() {
+    let selectors =
+        Selector::parse_all(["1-3".to_string(), "2021-5b,2020".to_string()], year(2019)).unwrap();
+
+    assert_eq!(
+        selectors,
+        [
+            Selector::Range(
+                Challenge::new(year(2019), Day::Day01, Part::PartA),
+                Challenge::new(year(2019), Day::Day03, Part::PartB),
+            ),
+            Selector::Challenge(Challenge::new(year(2021), Day::Day05, Part::PartB)),
+            Selector::All(year(2020)),
+        ]
+    );
+}
+
+#[test]
+fn answers_match_their_stored_form() {
+    assert!(Answer::from(24000u64).matches("24000"));
+    assert!(!Answer::from(24000u64).matches("24001"));
+    assert!(Answer::from("CMZ").matches("CMZ"));
+    assert!(Answer::grid(["#..#", ".##."]).matches("#..#\n.##.\n"));
+    assert!(!Answer::grid(["#..#", ".##."]).matches("#..#"));
+}
+
+#[test]
+fn pictures_cannot_be_submitted() {
+    assert_eq!(Answer::from(-3i64).submission().unwrap(), "-3");
+    assert!(Answer::grid(["#..#"]).submission().is_err());
+}
+
+#[test]
+fn reports_panics_as_failures() {
+    fn panics(_: &str) -> anyhow::Result<u8> {
+        panic!("the input is too short")
+    }
+
+    fn succeeds(_: &str) -> anyhow::Result<u8> {
+        Ok(42)
+    }
+
+    let broken = Challenge::new(Year::DEFAULT, Day::Day06, Part::PartA);
+    let working = Challenge::new(Year::DEFAULT, Day::Day06, Part::PartB);
+
+    let mut solutions = Solutions::new();
+    solutions
+        .insert(broken, panics as fn(&str) -> anyhow::Result<u8>)
+        .unwrap();
+    solutions
+        .insert(working, succeeds as fn(&str) -> anyhow::Result<u8>)
+        .unwrap();
+
+    let report = solutions.solve_input(&broken, "", &Parameters::new());
+    let error = report.error().unwrap();
+
+    assert!(
+        error.starts_with("panicked at tests/challenge.rs:"),
+        "{}",
+        error
+    );
+    assert!(error.ends_with(": the input is too short"), "{}", error);
+
+    let report = solutions.solve_input(&working, "", &Parameters::new());
+    assert_eq!(report.answer(), Some(&Answer::Integer(42)));
+}
+
+#[tokio::test]
+async fn abandons_solutions_which_run_out_of_time() {
+    fn searches(_: &str) -> anyhow::Result<u8> {
+        let cancellation = Cancellation::current();
+
+        loop {
+            cancellation.check()?;
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    fn succeeds(_: &str) -> anyhow::Result<u8> {
+        Ok(42)
+    }
+
+    let slow = Challenge::new(Year::DEFAULT, Day::Day16, Part::PartA);
+    let fast = Challenge::new(Year::DEFAULT, Day::Day16, Part::PartB);
+
+    let mut solutions = Solutions::new();
+    solutions
+        .insert(slow, searches as fn(&str) -> anyhow::Result<u8>)
+        .unwrap();
+    solutions
+        .insert(fast, succeeds as fn(&str) -> anyhow::Result<u8>)
+        .unwrap();
+    let solutions = Box::leak(Box::new(solutions));
+
+    let parameters = Arc::new(Parameters::new());
+    let limit = Some(Duration::from_millis(50));
+
+    let parsed = solutions
+        .parse_in_thread(
+            Year::DEFAULT,
+            Day::Day16,
+            Arc::from(""),
+            parameters.clone(),
+            limit,
+        )
+        .await
+        .map(Arc::new)
+        .unwrap();
+
+    let report = solutions
+        .solve_in_thread(slow, parsed.clone(), parameters.clone(), limit)
+        .await;
+    assert_eq!(report.error(), Some("timed out after 50ms"));
+
+    let report = solutions
+        .solve_in_thread(fast, parsed, parameters, limit)
+        .await;
+    assert_eq!(report.answer(), Some(&Answer::Integer(42)));
+}
+
+#[test]
+fn parts_share_the_parsed_model() {
+    static PARSED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Numbers(Vec<u32>);
+
+    fn parse(input: &[&str], _: &Parameters) -> anyhow::Result<Numbers> {
+        PARSED.fetch_add(1, Ordering::Relaxed);
+        let numbers = input
+            .iter()
+            .map(|line| line.parse())
+            .collect::<Result<_, _>>()?;
+        Ok(Numbers(numbers))
+    }
+
+    fn sum(numbers: &Numbers) -> anyhow::Result<u32> {
+        Ok(numbers.0.iter().sum())
+    }
+
+    fn product(numbers: &Numbers) -> anyhow::Result<u32> {
+        Ok(numbers.0.iter().product())
+    }
+
+    let registrations = [registration(Day::Day01, "numbers", |day| {
+        day.parsed(parse, sum, product)
+    })];
+    let solutions = Solutions::registered(&registrations).unwrap();
+
+    let parsed = solutions
+        .parse(Year::DEFAULT, Day::Day01, "2\n3\n4", &Parameters::new())
+        .unwrap();
+
+    let part_a = Challenge::new(Year::DEFAULT, Day::Day01, Part::PartA);
+    let part_b = Challenge::new(Year::DEFAULT, Day::Day01, Part::PartB);
+    let report_a = solutions.solve_parsed(&part_a, &parsed, &Parameters::new());
+    let report_b = solutions.solve_parsed(&part_b, &parsed, &Parameters::new());
+
+    assert_eq!(report_a.answer(), Some(&Answer::Integer(9)));
+    assert_eq!(report_b.answer(), Some(&Answer::Integer(24)));
+    assert!(parsed.duration.is_some());
+    assert_eq!(report_a.parse, parsed.duration);
+    assert_eq!(PARSED.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn functions_report_no_parse_time() {
+    let registrations = [registration(Day::Day01, "lines", both_parts)];
+    let solutions = Solutions::registered(&registrations).unwrap();
+
+    let challenge = Challenge::new(Year::DEFAULT, Day::Day01, Part::PartA);
+    let report = solutions.solve_input(&challenge, "1000\n\n2000", &Parameters::new());
+
+    assert_eq!(report.answer(), Some(&Answer::Integer(1)));
+    assert_eq!(report.parse, None);
+}
+
+#[test]
+fn cancellation_is_scoped_to_the_solution() {
+    let cancellation = Cancellation::new();
+    cancellation.cancel();
+
+    assert!(cancellation.scope(|| Cancellation::current().check().is_err()));
+    assert!(Cancellation::current().check().is_ok());
+}
+
+fn lines(_: &[&str]) -> anyhow::Result<u8> {
+    Ok(1)
+}
+
+fn both_parts(day: &mut DaySolutions) -> anyhow::Result<()> {
+    day.lines(Part::PartA, lines)?;
+    day.lines(Part::PartB, lines)
+}
+
+fn registration(
+    day: Day,
+    module: &'static str,
+    register: fn(&mut DaySolutions) -> anyhow::Result<()>,
+) -> Registration {
+    Registration {
+        year: Year::DEFAULT,
+        day,
+        title: "Test",
+        module,
+        register,
+    }
+}
+
+#[test]
+fn registers_every_day_of_2022() {
+    // Days of other years may be scaffolded next to them
+    let challenges = solutions()
+        .unwrap()
+        .challenges()
+        .filter(|challenge| challenge.year() == Year::DEFAULT)
+        .count();
+
+    assert_eq!(challenges, 49);
+    assert_eq!(
+        solutions().unwrap().title(Year::DEFAULT, Day::Day19),
+        Some("Not Enough Minerals")
+    );
+}
+
+#[test]
+fn rejects_days_registered_twice() {
+    let registrations = [
+        registration(Day::Day01, "first", both_parts),
+        registration(Day::Day01, "second", both_parts),
+    ];
+
+    let error = Solutions::registered(&registrations).err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "2022 Day 01 is registered by both first and second"
+    );
+
+    let registrations = [registration(Day::Day01, "twice", |day| {
+        both_parts(day)?;
+        day.lines(Part::PartB, lines)
+    })];
+
+    let error = Solutions::registered(&registrations).err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "twice registers 2022 Day 01 Part B more than once"
+    );
+}
+
+#[test]
+fn rejects_missing_parts_but_not_missing_days() {
+    let registrations = [registration(Day::Day01, "half", |day| {
+        day.lines(Part::PartA, lines)
+    })];
+
+    let error = Solutions::registered(&registrations).err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "half does not register 2022 Day 01 Part B"
+    );
+
+    let registrations = [
+        registration(Day::Day01, "first", both_parts),
+        registration(Day::Day03, "third", both_parts),
+    ];
+
+    let solutions = Solutions::registered(&registrations).unwrap();
+    assert_eq!(solutions.challenges().count(), 4);
+    assert_eq!(solutions.title(Year::DEFAULT, Day::Day02), None);
+}
+
+#[test]
+fn rejects_parts_inserted_twice() {
+    let challenge = Challenge::new(Year::DEFAULT, Day::Day04, Part::PartB);
+    let solution = lines as fn(&[&str]) -> anyhow::Result<u8>;
+
+    let mut solutions = Solutions::new();
+    solutions.insert(challenge, solution).unwrap();
+
+    let error = solutions.insert(challenge, solution).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "2022 Day 04 Part B is already registered"
+    );
+
+    let error = solutions
+        .insert_puzzle(Year::DEFAULT, Day::Day04, Functions::default())
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "2022 Day 04 Part B is already registered"
+    );
+}
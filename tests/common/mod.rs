@@ -0,0 +1,194 @@
+#![allow(dead_code)]
+
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use advent_of_code_2022::http::{Client, Config};
+use advent_of_code_2022::session::Credentials;
+
+pub const FLAG: &str = "0123456789abcdef";
+
+// Minimal HTTP server, which answers each connection with the next canned response and keeps the
+// requests it received, so that tests never talk to the real site
+pub struct MockServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+pub struct Response {
+    status: u16,
+    body: String,
+    delay: Duration,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl Response {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: body.into(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn ok(body: impl Into<String>) -> Self {
+        Response::new(200, body)
+    }
+
+    // Waits before answering, to trigger client timeouts
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+impl MockServer {
+    pub fn start(responses: Vec<Response>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let received = requests.clone();
+
+        let thread = std::thread::spawn(move || {
+            for response in responses {
+                let stream = match accept(&listener) {
+                    Some(stream) => stream,
+                    None => break,
+                };
+
+                let mut reader = BufReader::new(stream);
+
+                received.lock().unwrap().push(read_request(&mut reader));
+                std::thread::sleep(response.delay);
+
+                let message = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.status,
+                    response.body.len(),
+                    response.body
+                );
+
+                let _ = reader.get_mut().write_all(message.as_bytes());
+            }
+        });
+
+        MockServer {
+            url,
+            requests,
+            thread: Some(thread),
+        }
+    }
+
+    // Waits until every response has been sent, then returns the received requests
+    pub fn finish(mut self) -> Vec<Request> {
+        self.thread.take().unwrap().join().unwrap();
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+// Gives up after a while, so that a test which never sends a request fails instead of hanging
+fn accept(listener: &TcpListener) -> Option<TcpStream> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    listener.set_nonblocking(true).unwrap();
+
+    while Instant::now() < deadline {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false).unwrap();
+                return Some(stream);
+            }
+            Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(10))
+            }
+            Err(error) => panic!("Could not accept a connection: {}", error),
+        }
+    }
+
+    None
+}
+
+fn read_request(reader: &mut impl BufRead) -> Request {
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = Vec::new();
+
+    loop {
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+
+        match line.trim_end().split_once(": ") {
+            Some((key, value)) => headers.push((key.to_string(), value.to_string())),
+            None => break,
+        }
+    }
+
+    let length = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+        .map_or(0, |(_, value)| value.parse().unwrap());
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).unwrap();
+
+    Request {
+        method,
+        path,
+        headers,
+        body: String::from_utf8(body).unwrap(),
+    }
+}
+
+// Empty directory under the system temporary directory, unique to the test binary and the test
+pub fn temporary_directory(prefix: &str, name: &str) -> PathBuf {
+    let directory =
+        std::env::temp_dir().join(format!("aoc-{}-{}-{}", prefix, name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+// Client with a session given on the command line, which fails on the first error
+pub fn flag_client() -> Client {
+    Client::new(Config {
+        retries: 0,
+        ..flag_config()
+    })
+    .unwrap()
+}
+
+// Authenticates with FLAG instead of whatever session the environment holds
+pub fn flag_config() -> Config {
+    Config {
+        credentials: Credentials {
+            flag: Some(FLAG.to_string()),
+            ..Credentials::default()
+        },
+        ..Config::default()
+    }
+}
@@ -0,0 +1,230 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+
+use advent_of_code_2022::{solutions, Answer, Challenge, Day, Parameters, Part, Year};
+
+const EXAMPLES_DIRECTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/examples");
+
+// Each example lives in tests/examples/day_NN.txt (or day_NN_<suffix>.txt for additional ones),
+// examples of other years than the default one in a directory such as tests/examples/y2023.
+// The file starts with a header of "key: value" lines, where part_a and part_b hold the expected
+// answers and any other key is passed to the solution as a parameter. A key without a value is
+// followed by its value on lines indented with two spaces, for multi-line answers.
+// The header ends with "---", everything after it is the input.
+struct Example {
+    path: PathBuf,
+    answers: Vec<(Part, String)>,
+    parameters: Parameters,
+    input: String,
+}
+
+impl Example {
+    fn load(path: PathBuf) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+
+        let (header, input) = content
+            .split_once("---\n")
+            .with_context(|| format!("{} is missing the --- separator", path.display()))?;
+
+        let mut entries = Vec::<(&str, String)>::new();
+
+        for line in header.lines().filter(|line| !line.starts_with('#')) {
+            if let Some(continuation) = line.strip_prefix("  ") {
+                let (_, value) = entries
+                    .last_mut()
+                    .with_context(|| format!("{} does not continue any key", line))?;
+
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+
+                value.push_str(continuation);
+            } else if let Some(key) = line.strip_suffix(':') {
+                entries.push((key, String::new()));
+            } else {
+                let (key, value) = line
+                    .split_once(": ")
+                    .with_context(|| format!("{} is not a valid header line", line))?;
+
+                entries.push((key, value.to_string()));
+            }
+        }
+
+        let mut answers = Vec::new();
+        let mut parameters = Parameters::new();
+
+        for (key, value) in entries {
+            match key {
+                // Left empty until the example answer is filled in
+                "part_a" | "part_b" if value.is_empty() => {}
+                "part_a" => answers.push((Part::PartA, value)),
+                "part_b" => answers.push((Part::PartB, value)),
+                _ => parameters.insert(key, value),
+            }
+        }
+
+        if answers.is_empty() {
+            bail!("{} does not contain any expected answers", path.display());
+        }
+
+        Ok(Example {
+            path,
+            answers,
+            parameters,
+            input: input.to_string(),
+        })
+    }
+
+    fn run(&self, challenge: &Challenge) -> anyhow::Result<Answer> {
+        solutions()
+            .unwrap()
+            .run(challenge, &self.input, &self.parameters)
+    }
+}
+
+fn load_examples(year: Year, day: Day) -> Vec<Example> {
+    let prefix = format!("day_{:02}", u8::from(day));
+
+    let directory = match year {
+        Year::DEFAULT => Path::new(EXAMPLES_DIRECTORY).to_path_buf(),
+        _ => Path::new(EXAMPLES_DIRECTORY).join(format!("y{}", year)),
+    };
+
+    if !directory.exists() {
+        return Vec::new();
+    }
+
+    let mut paths = directory
+        .read_dir()
+        .expect("Could not read the examples directory")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_stem().unwrap().to_str().unwrap();
+            name == prefix || name.starts_with(&format!("{}_", prefix))
+        })
+        .collect::<Vec<_>>();
+
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| Example::load(path).unwrap())
+        .collect()
+}
+
+fn check_examples(year: Year, day: Day) {
+    let examples = load_examples(year, day);
+    assert!(
+        !examples.is_empty(),
+        "{} {} does not have any examples",
+        year,
+        day
+    );
+
+    let mut failures = Vec::new();
+
+    for example in &examples {
+        for (part, expected) in &example.answers {
+            let challenge = Challenge::new(year, day, *part);
+
+            match example.run(&challenge) {
+                Ok(actual) if actual.matches(expected) => {}
+                Ok(actual) => failures.push(format!(
+                    "{} ({}): expected {}, got {}",
+                    challenge,
+                    example.path.display(),
+                    expected,
+                    actual
+                )),
+                Err(error) => failures.push(format!(
+                    "{} ({}): {:?}",
+                    challenge,
+                    example.path.display(),
+                    error
+                )),
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn every_solution_has_an_example() {
+    let mut missing = solutions()
+        .unwrap()
+        .challenges()
+        .filter(|challenge| {
+            !load_examples(challenge.year(), challenge.day())
+                .iter()
+                .any(|example| {
+                    example
+                        .answers
+                        .iter()
+                        .any(|(part, _)| *part == challenge.part())
+                })
+        })
+        .collect::<Vec<_>>();
+
+    missing.sort();
+    assert!(missing.is_empty(), "Missing examples for {:?}", missing);
+}
+
+// Days of other years are scaffolded as they come, so they are checked together
+#[test]
+fn other_years() {
+    let mut days = solutions()
+        .unwrap()
+        .challenges()
+        .filter(|challenge| challenge.year() != Year::DEFAULT)
+        .map(|challenge| (challenge.year(), challenge.day()))
+        .collect::<Vec<_>>();
+
+    days.sort();
+    days.dedup();
+
+    for (year, day) in days {
+        check_examples(year, day);
+    }
+}
+
+macro_rules! example_tests {
+    ($($name:ident => $day:ident),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                check_examples(Year::DEFAULT, Day::$day);
+            }
+        )*
+    };
+}
+
+example_tests! {
+    day_01 => Day01,
+    day_02 => Day02,
+    day_03 => Day03,
+    day_04 => Day04,
+    day_05 => Day05,
+    day_06 => Day06,
+    day_07 => Day07,
+    day_08 => Day08,
+    day_09 => Day09,
+    day_10 => Day10,
+    day_11 => Day11,
+    day_12 => Day12,
+    day_13 => Day13,
+    day_14 => Day14,
+    day_15 => Day15,
+    day_16 => Day16,
+    day_17 => Day17,
+    day_18 => Day18,
+    day_19 => Day19,
+    day_20 => Day20,
+    day_21 => Day21,
+    day_22 => Day22,
+    day_23 => Day23,
+    day_24 => Day24,
+    day_25 => Day25,
+}
@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use advent_of_code_2022::http::{Client, Config, SessionExpired, USER_AGENT};
+
+use common::{flag_client, flag_config, MockServer, Response, FLAG};
+
+mod common;
+
+const EXPIRED: &str = "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n";
+
+fn client(retries: u32) -> Client {
+    Client::new(Config {
+        timeout: Duration::from_millis(500),
+        retries,
+        backoff: Duration::from_millis(10),
+        max_backoff: Duration::from_millis(20),
+        ..flag_config()
+    })
+    .unwrap()
+}
+
+#[tokio::test]
+async fn sends_the_session_and_user_agent() {
+    let server = MockServer::start(vec![Response::ok("1000\n2000\n")]);
+    let url = format!("{}/2022/day/1/input", server.url);
+    let body = flag_client().get(&url).await.unwrap();
+    let requests = server.finish();
+
+    assert_eq!(body, "1000\n2000\n");
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].path, "/2022/day/1/input");
+    assert_eq!(
+        requests[0].header("cookie"),
+        Some(format!("session={}", FLAG).as_str())
+    );
+    assert_eq!(requests[0].header("user-agent"), Some(USER_AGENT));
+}
+
+#[tokio::test]
+async fn retries_transient_failures() {
+    let server = MockServer::start(vec![
+        Response::new(500, "Internal Server Error"),
+        Response::new(503, "Service Unavailable"),
+        Response::ok("input"),
+    ]);
+
+    let body = client(3).get(&server.url).await.unwrap();
+    let requests = server.finish();
+
+    assert_eq!(body, "input");
+    assert_eq!(requests.len(), 3);
+}
+
+#[tokio::test]
+async fn retries_timeouts() {
+    let server = MockServer::start(vec![
+        Response::ok("late").delayed(Duration::from_millis(600)),
+        Response::ok("input"),
+    ]);
+
+    let body = client(1).get(&server.url).await.unwrap();
+    server.finish();
+
+    assert_eq!(body, "input");
+}
+
+#[tokio::test]
+async fn gives_up_after_the_last_retry() {
+    let server = MockServer::start(vec![
+        Response::new(502, "Bad Gateway"),
+        Response::new(502, "Bad Gateway"),
+        Response::new(502, "Bad Gateway"),
+    ]);
+
+    let error = client(2).get(&server.url).await.unwrap_err();
+    let requests = server.finish();
+
+    assert_eq!(requests.len(), 3);
+    assert!(
+        format!("{:#}", error).contains("Giving up after 3 attempts"),
+        "{:#}",
+        error
+    );
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors() {
+    let server = MockServer::start(vec![Response::new(404, "Not Found")]);
+    let error = client(3).get(&server.url).await.unwrap_err();
+    let requests = server.finish();
+
+    assert_eq!(requests.len(), 1);
+    assert!(format!("{:#}", error).contains("404"), "{:#}", error);
+}
+
+#[tokio::test]
+async fn reports_expired_sessions() {
+    let server = MockServer::start(vec![Response::new(400, EXPIRED)]);
+    let error = client(3).get(&server.url).await.unwrap_err();
+    let requests = server.finish();
+
+    assert_eq!(requests.len(), 1);
+    assert!(
+        error.downcast_ref::<SessionExpired>().is_some(),
+        "{:#}",
+        error
+    );
+}
+
+// An answer the site received must not be submitted again, so posts are never retried
+#[tokio::test]
+async fn does_not_retry_posts() {
+    let server = MockServer::start(vec![
+        Response::new(502, "Bad Gateway"),
+        Response::ok("late").delayed(Duration::from_millis(600)),
+        Response::ok("input"),
+    ]);
+
+    let client = client(3);
+    let url = format!("{}/2022/day/1/answer", server.url);
+    let form = [("level", "1"), ("answer", "24000")];
+
+    let error = client.post(&url, &form).await.unwrap_err();
+    assert!(format!("{:#}", error).contains("502"), "{:#}", error);
+    client.post(&url, &form).await.unwrap_err();
+
+    // The third response is only reached by a request which is not a retry of either post
+    let body = client.get(&server.url).await.unwrap();
+    let requests = server.finish();
+
+    assert_eq!(body, "input");
+    assert_eq!(
+        requests
+            .iter()
+            .map(|request| request.method.as_str())
+            .collect::<Vec<_>>(),
+        ["POST", "POST", "GET"]
+    );
+}
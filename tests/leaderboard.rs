@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use advent_of_code_2022::cache::Cache;
+use advent_of_code_2022::leaderboard::{self, Leaderboard, Sort};
+use advent_of_code_2022::{Day, Part, Year};
+
+use common::{flag_client, temporary_directory, MockServer, Response};
+
+mod common;
+
+// Recorded from the site, with the members renamed
+const LEADERBOARD: &str = include_str!("fixtures/leaderboard.json");
+
+fn names(leaderboard: &Leaderboard, sort: Sort) -> Vec<String> {
+    leaderboard
+        .sorted(sort)
+        .iter()
+        .map(|member| member.name())
+        .collect()
+}
+
+#[test]
+fn renders_the_members_by_local_score() {
+    let leaderboard = Leaderboard::parse(LEADERBOARD).unwrap();
+
+    assert_eq!(
+        leaderboard.render(Sort::Local),
+        concat!(
+            "   Name                   Score  Stars  123  Last star (UTC)\n",
+            "1) alice                     52      6  ***  2022-12-03 05:40:00\n",
+            "2) medja                     40      5  **+  2022-12-03 05:33:20\n",
+            "3) (anonymous user #303)      3      1  +..  2022-12-02 06:00:00\n",
+            "4) bob                        0      0  ...  -\n",
+        )
+    );
+}
+
+#[test]
+fn sorts_the_members() {
+    let leaderboard = Leaderboard::parse(LEADERBOARD).unwrap();
+
+    assert_eq!(
+        names(&leaderboard, Sort::Name),
+        ["(anonymous user #303)", "alice", "bob", "medja"]
+    );
+    assert_eq!(
+        names(&leaderboard, Sort::Stars),
+        ["alice", "medja", "(anonymous user #303)", "bob"]
+    );
+    assert_eq!(
+        names(&leaderboard, Sort::Last),
+        ["alice", "medja", "(anonymous user #303)", "bob"]
+    );
+}
+
+#[test]
+fn times_stars_from_the_release() {
+    let leaderboard = Leaderboard::parse(LEADERBOARD).unwrap();
+    let medja = &leaderboard.members["101"];
+
+    assert_eq!(
+        medja.time_to_star(Year::DEFAULT, Day::Day01, Part::PartA),
+        Some(Duration::from_secs(312))
+    );
+    assert_eq!(
+        medja.time_to_star(Year::DEFAULT, Day::Day03, Part::PartB),
+        None
+    );
+
+    let times = leaderboard.render_times(Year::DEFAULT, Sort::Local);
+    assert!(times.starts_with("alice\n  Day 01     00:03:20     00:06:40\n"));
+    assert!(times.contains("(anonymous user #303)\n  Day 01  1d 01:00:00            -\n"));
+    assert!(times.ends_with("bob\n"));
+}
+
+#[tokio::test]
+async fn caches_the_leaderboard_for_15_minutes() {
+    let server = MockServer::start(vec![Response::ok(LEADERBOARD)]);
+    let cache = Cache::new(temporary_directory("leaderboard", "cache"));
+    let client = flag_client();
+
+    let first = leaderboard::fetch(&client, &server.url, &cache, Year::DEFAULT, 101)
+        .await
+        .unwrap();
+    let second = leaderboard::fetch(&client, &server.url, &cache, Year::DEFAULT, 101)
+        .await
+        .unwrap();
+    let requests = server.finish();
+
+    assert_eq!(first, LEADERBOARD);
+    assert_eq!(second, LEADERBOARD);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/2022/leaderboard/private/view/101.json");
+
+    assert!(cache
+        .read_leaderboard(Year::DEFAULT, 101, Duration::ZERO)
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn does_not_cache_pages_which_are_not_leaderboards() {
+    let server = MockServer::start(vec![Response::ok("<html>Log in</html>")]);
+    let cache = Cache::new(temporary_directory("leaderboard", "invalid"));
+
+    let error = leaderboard::fetch(&flag_client(), &server.url, &cache, Year::DEFAULT, 101)
+        .await
+        .unwrap_err();
+    server.finish();
+
+    assert!(error
+        .to_string()
+        .starts_with("The leaderboard is not valid"));
+    assert!(!cache.leaderboard_path(Year::DEFAULT, 101).exists());
+}
@@ -0,0 +1,73 @@
+use advent_of_code_2022::ocr;
+
+#[test]
+fn reads_the_small_font() {
+    let rows = [
+        "#..#.####.#....#.....##..",
+        "#..#.#....#....#....#..#.",
+        "####.###..#....#....#..#.",
+        "#..#.#....#....#....#..#.",
+        "#..#.#....#....#....#..#.",
+        "#..#.####.####.####..##..",
+    ];
+
+    assert_eq!(ocr::read(&rows).unwrap(), "HELLO");
+}
+
+#[test]
+fn reads_narrow_and_wide_glyphs() {
+    let rows = [
+        ".###..#...#",
+        "..#...#...#",
+        "..#....#.#.",
+        "..#.....#..",
+        "..#.....#..",
+        ".###....#..",
+    ];
+
+    assert_eq!(ocr::read(&rows).unwrap(), "IY");
+}
+
+#[test]
+fn reads_the_large_font() {
+    let rows = [
+        "#....#..#....#..######",
+        "##...#..#....#.......#",
+        "##...#...#..#........#",
+        "#.#..#...#..#.......#.",
+        "#.#..#....##.......#..",
+        "#..#.#....##......#...",
+        "#..#.#...#..#....#....",
+        "#...##...#..#...#.....",
+        "#...##..#....#..#.....",
+        "#....#..#....#..######",
+    ];
+
+    assert_eq!(ocr::read(&rows).unwrap(), "NXZ");
+}
+
+#[test]
+fn shows_unknown_glyphs() {
+    let rows = [
+        "#..#.#...#",
+        "#..#.##.##",
+        "####.#.#.#",
+        "#..#.#...#",
+        "#..#.#...#",
+        "#..#.#...#",
+    ];
+
+    let error = ocr::read(&rows).unwrap_err().to_string();
+
+    assert!(error.contains("column 5"), "{}", error);
+    assert!(
+        error.contains("#...#\n##.##\n#.#.#\n#...#\n#...#\n#...#"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn rejects_pictures_of_other_heights() {
+    assert!(ocr::read(&["####", "#..."]).is_err());
+}
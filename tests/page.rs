@@ -0,0 +1,146 @@
+use advent_of_code_2022::cache::Cache;
+use advent_of_code_2022::page::{self, Format, Page};
+use advent_of_code_2022::scaffold;
+use advent_of_code_2022::{Day, Year};
+
+use common::{flag_client, temporary_directory, MockServer, Response};
+
+mod common;
+
+const URL: &str = "https://adventofcode.com/2022/day/1";
+
+const PART_ONE: &str = "<article class=\"day-desc\"><h2>--- Day 1: Calorie Counting ---</h2><p>The Elves take turns writing down the number of <em>Calories</em> contained by the various meals.</p>
+<p>For example, suppose the Elves finish writing their items' Calories into a list:</p>
+<pre><code>1000
+2000
+
+3000
+</code></pre>
+<p>This list represents the Calories of the food carried by two Elves:</p>
+<ul>
+<li>The first Elf is carrying food with <code>1000</code> and <code>2000</code> Calories, a total of <code><em>3000</em></code> Calories.</li>
+<li>The second Elf is carrying one food item with <code>3000</code> Calories.</li>
+</ul>
+<p>Find the Elf carrying the most Calories. <em>How many total Calories is that Elf carrying?</em></p>
+</article>";
+
+const PART_TWO: &str = "<article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2><p>By the time you calculate the answer to the Elves' question, they've already realized that the Elf carrying the most Calories of food might eventually <a href=\"/2022/day/1/input\">run out of snacks</a> &amp; <span title=\"Yum.\">stop</span>.</p>
+</article>";
+
+fn page(articles: &[&str]) -> String {
+    format!(
+        "<!DOCTYPE html><html><body><main>{}<p>Your puzzle answer was <code>69281</code>.</p></main></body></html>",
+        articles.join("")
+    )
+}
+
+#[test]
+fn renders_both_parts_as_text() {
+    let page = Page::parse(&page(&[PART_ONE, PART_TWO]), URL).unwrap();
+
+    assert_eq!(
+        page.render(Format::Text),
+        "--- Day 1: Calorie Counting ---
+
+The Elves take turns writing down the number of Calories contained by the
+various meals.
+
+For example, suppose the Elves finish writing their items' Calories into a list:
+
+    1000
+    2000
+
+    3000
+
+This list represents the Calories of the food carried by two Elves:
+
+  - The first Elf is carrying food with 1000 and 2000 Calories, a total of 3000
+    Calories.
+  - The second Elf is carrying one food item with 3000 Calories.
+
+Find the Elf carrying the most Calories. How many total Calories is that Elf
+carrying?
+
+--- Part Two ---
+
+By the time you calculate the answer to the Elves' question, they've already
+realized that the Elf carrying the most Calories of food might eventually run
+out of snacks & stop.
+"
+    );
+}
+
+#[test]
+fn renders_markdown() {
+    let page = Page::parse(&page(&[PART_ONE, PART_TWO]), URL).unwrap();
+    let markdown = page.render(Format::Markdown);
+
+    assert!(markdown.starts_with("## --- Day 1: Calorie Counting ---\n\n"));
+    assert!(markdown.contains("number of **Calories** contained"));
+    assert!(markdown.contains("\n```\n1000\n2000\n\n3000\n```\n"));
+    assert!(markdown.contains("- The first Elf is carrying food with `1000` and `2000` Calories, a total of **`3000`** Calories.\n"));
+    assert!(
+        markdown.contains("[run out of snacks](https://adventofcode.com/2022/day/1/input) & stop.")
+    );
+    assert!(!markdown.contains("69281"));
+}
+
+#[test]
+fn saves_the_examples_as_fixtures() {
+    let page = Page::parse(&page(&[PART_ONE]), URL).unwrap();
+    assert_eq!(page.examples(), ["1000\n2000\n\n3000\n"]);
+
+    let root = temporary_directory("page", "examples");
+    let examples = root.join("tests").join("examples");
+    std::fs::create_dir_all(&examples).unwrap();
+    std::fs::write(examples.join("day_01.txt"), "part_a:\npart_b:\n---\n").unwrap();
+    std::fs::write(examples.join("day_02.txt"), "part_a: 15\n---\nA Y\n").unwrap();
+
+    let written =
+        scaffold::save_examples(&root, Year::DEFAULT, Day::Day01, &page.examples()).unwrap();
+    assert_eq!(written, [examples.join("day_01.txt")]);
+    assert_eq!(
+        std::fs::read_to_string(examples.join("day_01.txt")).unwrap(),
+        "part_a:\npart_b:\n---\n1000\n2000\n\n3000\n"
+    );
+
+    let written =
+        scaffold::save_examples(&root, Year::DEFAULT, Day::Day02, &["A Y\n", "B X\n"]).unwrap();
+    assert_eq!(written, [examples.join("day_02_2.txt")]);
+    assert_eq!(
+        std::fs::read_to_string(examples.join("day_02.txt")).unwrap(),
+        "part_a: 15\n---\nA Y\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(examples.join("day_02_2.txt")).unwrap(),
+        "# https://adventofcode.com/2022/day/2\npart_a:\npart_b:\n---\nB X\n"
+    );
+}
+
+#[tokio::test]
+async fn caches_the_downloaded_page_next_to_the_input() {
+    let html = page(&[PART_ONE, PART_TWO]);
+    let server = MockServer::start(vec![Response::ok(html.clone())]);
+    let client = flag_client();
+
+    let cache = Cache::new(temporary_directory("page", "cache"));
+    let downloaded = page::fetch(&client, &server.url, &cache, Year::DEFAULT, Day::Day01)
+        .await
+        .unwrap();
+    let requests = server.finish();
+
+    assert_eq!(downloaded, html);
+    assert_eq!(requests[0].path, "/2022/day/1");
+    assert!(cache
+        .page_path(Year::DEFAULT, Day::Day01)
+        .ends_with("2022/day01.html"));
+    assert_eq!(
+        cache.read_page(Year::DEFAULT, Day::Day01).unwrap(),
+        Some(html.clone())
+    );
+
+    // A single part is all there is until the first one is solved
+    assert!(!page::is_stale(&page(&[PART_ONE]), false));
+    assert!(page::is_stale(&page(&[PART_ONE]), true));
+    assert!(!page::is_stale(&html, true));
+}
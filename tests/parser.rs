@@ -0,0 +1,171 @@
+use advent_of_code_2022::{solutions, Challenge, Day, Parameters, ParseError};
+
+fn parse_error(challenge: &str, input: &str) -> ParseError {
+    let challenge = challenge.parse::<Challenge>().unwrap();
+    let error = solutions()
+        .unwrap()
+        .run(&challenge, input, &Parameters::new())
+        .unwrap_err();
+
+    match error.downcast::<ParseError>() {
+        Ok(error) => error,
+        Err(error) => panic!("{} did not fail with a parse error: {:?}", challenge, error),
+    }
+}
+
+fn error(challenge: &str, input: &str) -> String {
+    let challenge = challenge.parse::<Challenge>().unwrap();
+    let error = solutions()
+        .unwrap()
+        .run(&challenge, input, &Parameters::new())
+        .unwrap_err();
+
+    format!("{:#}", error)
+}
+
+#[test]
+fn points_at_truncated_blueprints() {
+    let input = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2";
+    let error = parse_error("19a", input);
+
+    assert_eq!(error.day, Day::Day19);
+    assert_eq!(error.line, 1);
+    assert_eq!(error.column, 65);
+    assert_eq!(error.text, "");
+    assert_eq!(
+        error.to_string(),
+        "Day 19, line 1, column 65: expected \" ore. Each obsidian robot costs \", found the end of the line"
+    );
+}
+
+#[test]
+fn points_at_malformed_numbers() {
+    let input = "Blueprint 1: Each ore robot costs four ore.";
+    let error = parse_error("19b", input);
+
+    assert_eq!(error.column, 35);
+    assert_eq!(error.text, "four ore.");
+    assert_eq!(error.expected, "a number");
+}
+
+#[test]
+fn points_at_unknown_instructions() {
+    let error = parse_error("10a", "noop\naddx 3\nmulx 2");
+
+    assert_eq!(error.day, Day::Day10);
+    assert_eq!(error.line, 3);
+    assert_eq!(error.column, 1);
+    assert_eq!(error.expected, "noop or addx");
+
+    let error = parse_error("10b", "addx");
+    assert_eq!((error.line, error.column), (1, 5));
+}
+
+#[test]
+fn points_at_malformed_monkeys() {
+    let error = parse_error("21a", "root: pppw + sjmn\npppw: 2\nsjmn 3");
+    assert_eq!((error.line, error.column), (3, 7));
+    assert_eq!(error.expected, "':'");
+
+    let error = parse_error("21b", "root: pppw % sjmn\npppw: 2\nsjmn: 3");
+    assert_eq!((error.line, error.column), (1, 12));
+
+    let error = parse_error("21a", "root: pppw + sjmn\npppw: 2");
+    assert_eq!((error.line, error.column), (1, 14));
+    assert_eq!(error.text, "sjmn");
+}
+
+#[test]
+fn rejects_programs_which_end_early() {
+    assert_eq!(
+        error("10a", "noop\naddx 3"),
+        "The program ends after 3 cycles, before cycle 20"
+    );
+    assert_eq!(
+        error("10b", "noop\naddx 3"),
+        "The program ends after 3 cycles, before the screen is drawn"
+    );
+}
+
+#[test]
+fn points_at_monkeys_waiting_for_themselves() {
+    let error = parse_error(
+        "21a",
+        "root: pppw + sjmn\npppw: sjmn * dbpl\ndbpl: 2\nsjmn: pppw - dbpl",
+    );
+    assert_eq!((error.line, error.column), (4, 7));
+    assert_eq!(error.text, "pppw - dbpl");
+    assert_eq!(error.expected, "a monkey which does not wait for itself");
+
+    let error = parse_error("21b", "root: root + sjmn\nsjmn: 3");
+    assert_eq!((error.line, error.column), (1, 7));
+}
+
+#[test]
+fn rejects_jobs_which_cannot_be_computed() {
+    assert_eq!(
+        error("21a", "root: pppw / sjmn\npppw: 4\nsjmn: 0"),
+        "Monkey root cannot compute 4 / 0"
+    );
+    assert_eq!(
+        error("21a", "root: pppw - sjmn\npppw: 4\nsjmn: 5"),
+        "Monkey root cannot compute 4 - 5"
+    );
+    assert_eq!(
+        error("21b", "root: pppw + sjmn\npppw: 4\nsjmn: 5\nhumn: 1"),
+        "Neither side of the root monkey depends on the human"
+    );
+    assert_eq!(
+        error(
+            "21b",
+            "root: pppw + sjmn\npppw: humn * dbpl\nsjmn: humn - dbpl\nhumn: 1\ndbpl: 2"
+        ),
+        "Both sides of the root monkey depend on the human"
+    );
+    assert_eq!(
+        error(
+            "21b",
+            "root: pppw + sjmn\npppw: humn * dbpl\nsjmn: 5\nhumn: 1\ndbpl: 2"
+        ),
+        "Monkey pppw cannot yell 5 whatever the human yells"
+    );
+}
+
+// Whether the answer is an error or not, none of the solutions should panic
+#[test]
+fn survives_empty_and_garbled_inputs() {
+    let challenges = (1..=25)
+        .flat_map(|day| ["a", "b"].map(|part| format!("{}{}", day, part)))
+        .filter(|challenge| challenge != "25b");
+
+    for challenge in challenges {
+        let challenge = challenge.parse::<Challenge>().unwrap();
+
+        for input in ["", "\n", "???\n!!!"] {
+            let _ = solutions()
+                .unwrap()
+                .run(&challenge, input, &Parameters::new());
+        }
+    }
+}
+
+#[test]
+fn points_at_unknown_valves() {
+    let error = parse_error(
+        "16a",
+        "Valve AA has flow rate=0; tunnels lead to valves BB, CC\nValve BB has flow rate=3; tunnel leads to valve AA",
+    );
+
+    assert_eq!(error.day, Day::Day16);
+    assert_eq!((error.line, error.column), (1, 54));
+    assert_eq!(error.expected, "the name of a listed valve");
+}
+
+#[test]
+fn rejects_maps_without_a_path() {
+    assert_eq!(error("22a", ""), "Expected a map, a blank line and a path");
+
+    let error = parse_error("22a", "...#\n.#..\n\n10R5x");
+    assert_eq!((error.line, error.column), (4, 5));
+    assert_eq!(error.expected, "one of L R");
+}
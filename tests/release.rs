@@ -0,0 +1,45 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use advent_of_code_2022::release::{self, format_countdown};
+use advent_of_code_2022::{Day, Year};
+
+fn timestamp(year: u16, day: u8) -> u64 {
+    let year = Year::new(year).unwrap();
+    let day = Day::try_from(day).unwrap();
+
+    release::release_time(year, day)
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[test]
+fn releases_at_midnight_eastern_standard_time() {
+    // 2022-12-01T05:00:00Z and 2022-12-25T05:00:00Z
+    assert_eq!(timestamp(2022, 1), 1669870800);
+    assert_eq!(timestamp(2022, 25), 1671944400);
+    // 2015-12-01T05:00:00Z and 2024-12-01T05:00:00Z, across leap years
+    assert_eq!(timestamp(2015, 1), 1448946000);
+    assert_eq!(timestamp(2024, 1), 1733029200);
+}
+
+#[test]
+fn past_puzzles_are_released() {
+    assert_eq!(Day::Day01.time_until_release(Year::DEFAULT), None);
+    assert!(release::check_released(Year::DEFAULT, Day::Day25).is_ok());
+}
+
+#[test]
+fn formats_countdowns() {
+    assert_eq!(format_countdown(Duration::from_secs(0)), "00:00:00");
+    assert_eq!(format_countdown(Duration::from_millis(1500)), "00:00:02");
+    assert_eq!(format_countdown(Duration::from_secs(3723)), "01:02:03");
+    assert_eq!(format_countdown(Duration::from_secs(90061)), "1d 01:01:01");
+}
+
+#[test]
+fn formats_timestamps_in_utc() {
+    assert_eq!(release::format_timestamp(1669870800), "2022-12-01 05:00:00");
+    assert_eq!(release::format_timestamp(1709251199), "2024-02-29 23:59:59");
+    assert_eq!(release::format_timestamp(0), "1970-01-01 00:00:00");
+}
@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::runner;
+use advent_of_code_2022::{Answer, Challenge, Day, Parameters, Part, Report, Year};
+
+fn input_file(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("aoc-runner-{}-{}.txt", name, std::process::id()));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+fn report(day: Day, part: Part, result: Result<Answer, String>, parse: Option<u64>) -> Report {
+    Report {
+        challenge: Challenge::new(Year::DEFAULT, day, part),
+        result,
+        parse: parse.map(Duration::from_micros),
+        duration: Duration::from_micros(5),
+    }
+}
+
+#[tokio::test]
+async fn reports_in_challenge_order() {
+    let source = InputSource::File(input_file("order", "1000\n\n2000\n3000\n\n4000\n"));
+    let challenges = ["1b", "1a", "6a"]
+        .map(|challenge| challenge.parse::<Challenge>().unwrap())
+        .to_vec();
+
+    let mut reported = Vec::new();
+    let reports = runner::run(&challenges, source, Parameters::new(), 2, None, |report| {
+        reported.push(report.challenge);
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(reported, challenges);
+
+    let answers = reports
+        .iter()
+        .map(|report| (report.challenge, report.answer().cloned()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        answers,
+        [
+            (challenges[0], Some(Answer::Integer(10000))),
+            (challenges[1], Some(Answer::Integer(5000))),
+            (challenges[2], None),
+        ]
+    );
+}
+
+#[test]
+fn summarizes_parse_times_once_per_day() {
+    let reports = [
+        report(Day::Day21, Part::PartA, Ok(Answer::from(152)), None),
+        report(Day::Day19, Part::PartA, Ok(Answer::from(33)), Some(40)),
+        report(
+            Day::Day19,
+            Part::PartB,
+            Err("timed out".to_string()),
+            Some(40),
+        ),
+    ];
+
+    let mut out = Vec::new();
+    runner::print_summary(&mut out, &reports, Duration::from_micros(70)).unwrap();
+    let summary = String::from_utf8(out).unwrap();
+
+    assert!(summary.contains("Parse (1 inputs)"), "{}", summary);
+    assert!(summary.contains("Solve (3 challenges)"), "{}", summary);
+
+    // Day 21 parses while solving, so its parse time is left out
+    let day_21 = summary
+        .lines()
+        .find(|line| line.starts_with("Day 21"))
+        .unwrap();
+    assert_eq!(day_21.split_whitespace().rev().nth(1), Some("-"));
+
+    let total = summary
+        .lines()
+        .find(|line| line.starts_with("Total"))
+        .unwrap();
+    assert!(total.ends_with("55µs"), "{}", total);
+}
+
+#[test]
+fn lists_the_failed_challenges() {
+    let failed = [Challenge::new(Year::DEFAULT, Day::Day19, Part::PartB)];
+
+    let mut out = Vec::new();
+    runner::print_results(&mut out, 3, &failed).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!("\n2 passed, 1 failed\n  {}\n", failed[0])
+    );
+}
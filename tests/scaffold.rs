@@ -0,0 +1,167 @@
+use std::path::{Path, PathBuf};
+
+use advent_of_code_2022::answers::Answers;
+use advent_of_code_2022::scaffold;
+use advent_of_code_2022::{Challenge, Day, Part, Year};
+
+use common::{flag_client, temporary_directory, MockServer, Response};
+
+mod common;
+
+const PUZZLE: &str = "<main><article class=\"day-desc\"><h2>--- Day 21: Monkey Math &amp; More ---</h2><p>The <a href=\"11\">monkeys</a> are back!</p></article></main>";
+
+const MODULES: &str = "mod answer;
+pub mod day_01;
+pub mod day_03;
+mod panic;
+";
+
+fn temporary_repository(name: &str) -> PathBuf {
+    let root = temporary_directory("new", name);
+    std::fs::create_dir_all(root.join("src").join("challenge")).unwrap();
+    std::fs::create_dir_all(root.join("tests").join("examples")).unwrap();
+    std::fs::write(root.join("src").join("challenge").join("mod.rs"), MODULES).unwrap();
+    root
+}
+
+fn read(root: &Path, path: &str) -> String {
+    std::fs::read_to_string(root.join(path)).unwrap()
+}
+
+#[tokio::test]
+async fn fetches_the_title_from_the_puzzle_page() {
+    let server = MockServer::start(vec![Response::ok(PUZZLE)]);
+    let client = flag_client();
+
+    let title = scaffold::fetch_title(&client, &server.url, Year::DEFAULT, Day::Day21)
+        .await
+        .unwrap();
+    let requests = server.finish();
+
+    assert_eq!(title, "Monkey Math & More");
+    assert_eq!(requests[0].path, "/2022/day/21");
+}
+
+#[test]
+fn creates_and_registers_the_module() {
+    let root = temporary_repository("create");
+    let mut answers = Answers::load(root.join("answers.toml")).unwrap();
+
+    let written = scaffold::create(
+        &root,
+        &mut answers,
+        Year::DEFAULT,
+        Day::Day02,
+        "Rock \"Paper\"",
+    )
+    .unwrap();
+    answers.save().unwrap();
+
+    assert_eq!(written.len(), 3);
+    assert_eq!(
+        read(&root, "src/challenge/mod.rs"),
+        "mod answer;\npub mod day_01;\npub mod day_02;\npub mod day_03;\nmod panic;\n"
+    );
+
+    let module = read(&root, "src/challenge/day_02.rs");
+    assert!(module.contains("    day: Day02,\n    title: \"Rock \\\"Paper\\\"\",\n"));
+    assert!(module.contains("pub fn part_a(input: &[&str])"));
+    assert!(module.contains("pub fn part_b(input: &[&str])"));
+
+    assert_eq!(
+        read(&root, "tests/examples/day_02.txt"),
+        "# https://adventofcode.com/2022/day/2\npart_a:\npart_b:\n---\n"
+    );
+    assert_eq!(read(&root, "answers.toml"), "02a = \"\"\n02b = \"\"\n");
+
+    let challenge = Challenge::new(Year::DEFAULT, Day::Day02, Part::PartA);
+    assert_eq!(answers.get(&challenge), None);
+}
+
+#[test]
+fn refuses_to_overwrite_a_module() {
+    let root = temporary_repository("overwrite");
+    let module = root.join("src").join("challenge").join("day_03.rs");
+    std::fs::write(&module, "// solved").unwrap();
+
+    let error = scaffold::check_new(&root, Year::DEFAULT, Day::Day03).unwrap_err();
+    assert!(error.to_string().ends_with("day_03.rs already exists"));
+    assert!(scaffold::check_new(&root, Year::DEFAULT, Day::Day04).is_ok());
+
+    let mut answers = Answers::load(root.join("answers.toml")).unwrap();
+    let error =
+        scaffold::create(&root, &mut answers, Year::DEFAULT, Day::Day03, "Rucksack").unwrap_err();
+
+    assert!(error.to_string().ends_with("day_03.rs already exists"));
+    assert_eq!(std::fs::read_to_string(&module).unwrap(), "// solved");
+    assert_eq!(read(&root, "src/challenge/mod.rs"), MODULES);
+}
+
+#[test]
+fn keeps_existing_examples_and_answers() {
+    let root = temporary_repository("existing");
+    let fixture = root.join("tests").join("examples").join("day_25.txt");
+    std::fs::write(&fixture, "part_a: 2=-1=0\n---\n1=-0-2\n").unwrap();
+    std::fs::write(root.join("answers.toml"), "25a = \"2=-1=0\"\n").unwrap();
+
+    let mut answers = Answers::load(root.join("answers.toml")).unwrap();
+    let written = scaffold::create(
+        &root,
+        &mut answers,
+        Year::DEFAULT,
+        Day::Day25,
+        "Full of Hot Air",
+    )
+    .unwrap();
+    answers.save().unwrap();
+
+    assert_eq!(written.len(), 2);
+    assert!(!read(&root, "src/challenge/day_25.rs").contains("part_b"));
+    assert_eq!(
+        std::fs::read_to_string(&fixture).unwrap(),
+        "part_a: 2=-1=0\n---\n1=-0-2\n"
+    );
+    assert_eq!(read(&root, "answers.toml"), "25a = \"2=-1=0\"\n");
+}
+
+#[test]
+fn creates_days_of_other_years_in_their_own_module() {
+    let root = temporary_repository("year");
+    let year = Year::new(2023).unwrap();
+    let mut answers = Answers::load(root.join("answers.toml")).unwrap();
+
+    let written = scaffold::create(&root, &mut answers, year, Day::Day01, "Trebuchet?!").unwrap();
+    answers.save().unwrap();
+
+    assert_eq!(written.len(), 4);
+    assert_eq!(
+        read(&root, "src/challenge/mod.rs"),
+        "mod answer;\npub mod day_01;\npub mod day_03;\nmod panic;\npub mod y2023;\n"
+    );
+    assert_eq!(
+        read(&root, "src/challenge/y2023/mod.rs"),
+        "pub mod day_01;\n"
+    );
+
+    let module = read(&root, "src/challenge/y2023/day_01.rs");
+    assert!(module.starts_with("use crate::challenge::register;\n"));
+    assert!(module.contains("    year: 2023,\n    day: Day01,\n"));
+
+    assert_eq!(
+        read(&root, "tests/examples/y2023/day_01.txt"),
+        "# https://adventofcode.com/2023/day/1\npart_a:\npart_b:\n---\n"
+    );
+    assert_eq!(
+        read(&root, "answers.toml"),
+        "2023-01a = \"\"\n2023-01b = \"\"\n"
+    );
+
+    let written =
+        scaffold::create(&root, &mut answers, year, Day::Day02, "Cube Conundrum").unwrap();
+
+    assert_eq!(written.len(), 3);
+    assert_eq!(
+        read(&root, "src/challenge/y2023/mod.rs"),
+        "pub mod day_01;\npub mod day_02;\n"
+    );
+}
@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use advent_of_code_2022::http::SessionExpired;
+use advent_of_code_2022::session::{self, Credentials, Session, Source};
+use advent_of_code_2022::Year;
+
+use common::{flag_client, temporary_directory, MockServer, Response, FLAG};
+
+mod common;
+
+const SECRET: &str = "53616c7465645f5f0123456789abcdef";
+
+const LOGGED_IN: &str = "<header><div><h1 class=\"title-global\"><a href=\"/\">Advent of Code</a></h1><nav><ul><li><a href=\"/2022/about\">[About]</a></li></ul></nav><div class=\"user\">medja <span class=\"star-count\">50*</span></div></div></header>";
+const LOGGED_OUT: &str = "<header><div><h1 class=\"title-global\"><a href=\"/\">Advent of Code</a></h1><nav><ul><li><a href=\"/2022/auth/login\">[Log In]</a></li></ul></nav></div></header>";
+
+fn write_session(path: &PathBuf, value: &str, mode: u32) {
+    std::fs::write(path, value).unwrap();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+}
+
+#[test]
+fn resolves_the_credential_chain_in_order() {
+    let directory = temporary_directory("session", "chain");
+    let config_file = directory.join("session");
+    let token_file = directory.join("token");
+    write_session(&config_file, "c0ffee\n", 0o600);
+    write_session(&token_file, "7e57\n", 0o600);
+
+    let mut credentials = Credentials {
+        environment: Some("e4e4".to_string()),
+        flag: Some("f1a9".to_string()),
+        config_file: Some(config_file.clone()),
+        token_file: Some(token_file.clone()),
+    };
+
+    assert_eq!(credentials.resolve().unwrap().1, Source::Environment);
+
+    credentials.environment = Some(String::new());
+    assert_eq!(credentials.resolve().unwrap().1, Source::Flag);
+
+    credentials.flag = None;
+    assert_eq!(
+        credentials.resolve().unwrap().1,
+        Source::ConfigFile(config_file.clone())
+    );
+
+    std::fs::remove_file(&config_file).unwrap();
+    let (session, source) = credentials.resolve().unwrap();
+    assert_eq!(source, Source::TokenFile(token_file.clone()));
+    assert_eq!(session.cookie(), "session=7e57");
+
+    credentials.token_file = None;
+    assert!(credentials.resolve().is_err());
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn refuses_config_files_readable_by_others() {
+    let directory = temporary_directory("session", "permissions");
+    let config_file = directory.join("session");
+    write_session(&config_file, SECRET, 0o644);
+
+    let credentials = Credentials {
+        config_file: Some(config_file.clone()),
+        ..Credentials::default()
+    };
+
+    let error = credentials.resolve().unwrap_err();
+    assert!(format!("{:#}", error).contains("chmod 600"), "{:#}", error);
+
+    write_session(&config_file, SECRET, 0o600);
+    assert!(credentials.resolve().is_ok());
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn never_shows_the_secret() {
+    let session = Session::new(SECRET).unwrap();
+    assert!(!format!("{:?}", session).contains(SECRET));
+
+    let credentials = Credentials {
+        environment: Some(SECRET.to_string()),
+        flag: Some(SECRET.to_string()),
+        ..Credentials::default()
+    };
+
+    assert!(!format!("{:?}", credentials).contains(SECRET));
+
+    let invalid = format!("{}!", SECRET);
+    let error = Session::new(&invalid).unwrap_err();
+    assert!(!format!("{:?}", error).contains(SECRET));
+}
+
+#[tokio::test]
+async fn shows_the_logged_in_user() {
+    let server = MockServer::start(vec![Response::ok(LOGGED_IN)]);
+    let client = flag_client();
+
+    let year = Year::new(2023).unwrap();
+    let user = session::whoami(&client, &server.url, year).await.unwrap();
+    let requests = server.finish();
+
+    assert_eq!(user, "medja");
+    assert_eq!(requests[0].path, "/2023");
+    assert_eq!(
+        requests[0].header("cookie"),
+        Some(format!("session={}", FLAG).as_str())
+    );
+}
+
+#[tokio::test]
+async fn reports_sessions_which_do_not_work_anymore() {
+    let server = MockServer::start(vec![Response::ok(LOGGED_OUT)]);
+    let client = flag_client();
+
+    let error = session::whoami(&client, &server.url, Year::DEFAULT)
+        .await
+        .unwrap_err();
+    server.finish();
+
+    assert!(
+        error.downcast_ref::<SessionExpired>().is_some(),
+        "{:#}",
+        error
+    );
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use advent_of_code_2022::leaderboard::Leaderboard;
+use advent_of_code_2022::stats::Stats;
+use advent_of_code_2022::{Answer, Challenge, Day, Part, Report, Year};
+
+const LEADERBOARD: &str = include_str!("fixtures/leaderboard.json");
+
+fn report(day: Day, part: Part, result: Result<Answer, String>, micros: u64) -> Report {
+    Report {
+        challenge: Challenge::new(Year::DEFAULT, day, part),
+        result,
+        parse: Some(Duration::from_micros(micros)),
+        duration: Duration::from_micros(micros),
+    }
+}
+
+fn stats() -> Stats {
+    let leaderboard = Leaderboard::parse(LEADERBOARD).unwrap();
+    let medja = leaderboard.find("medja").unwrap();
+
+    let reports = [
+        report(Day::Day01, Part::PartA, Ok(Answer::from(24000)), 10),
+        report(Day::Day01, Part::PartB, Ok(Answer::from(45000)), 20),
+        report(Day::Day02, Part::PartA, Err("Invalid move".to_string()), 5),
+    ];
+
+    Stats::new(Year::DEFAULT, medja, &reports)
+}
+
+#[test]
+fn finds_members_by_id_or_name() {
+    let leaderboard = Leaderboard::parse(LEADERBOARD).unwrap();
+
+    assert_eq!(leaderboard.find("202").unwrap().name(), "alice");
+    assert_eq!(leaderboard.find("alice").unwrap().id, 202);
+    assert_eq!(leaderboard.find("(anonymous user #303)").unwrap().id, 303);
+    assert!(leaderboard.find("carol").is_none());
+}
+
+#[test]
+fn renders_december_as_a_calendar() {
+    assert_eq!(
+        stats().render_calendar(),
+        concat!(
+            "medja, 2022: 5 stars\n",
+            "\n",
+            "Mon    Tue    Wed    Thu    Fri    Sat    Sun\n",
+            "                      1 **   2 **   3 *.   4 ..\n",
+            " 5 ..   6 ..   7 ..   8 ..   9 ..  10 ..  11 ..\n",
+            "12 ..  13 ..  14 ..  15 ..  16 ..  17 ..  18 ..\n",
+            "19 ..  20 ..  21 ..  22 ..  23 ..  24 ..  25 ..\n",
+        )
+    );
+}
+
+#[test]
+fn times_stars_and_solutions() {
+    assert_eq!(
+        stats().render_table(),
+        concat!(
+            "Day       Part A    Part B    A to B  Runtime A  Runtime B\n",
+            "Day 01  00:05:12  00:10:45  00:05:33       20µs       40µs\n",
+            "Day 02  00:16:40  00:25:00  00:08:20          -          -\n",
+            "Day 03  00:33:20         -         -          -          -\n",
+        )
+    );
+}
+
+#[test]
+fn exports_every_day_as_csv() {
+    let csv = stats().to_csv();
+    let lines = csv.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines.len(), 26);
+    assert_eq!(
+        lines[0],
+        "day,part_a_star,part_b_star,part_a_seconds,part_b_seconds,gap_seconds,part_a_runtime_ns,part_b_runtime_ns"
+    );
+    assert_eq!(
+        lines[1],
+        "1,2022-12-01 05:05:12,2022-12-01 05:10:45,312,645,333,20000,40000"
+    );
+    assert_eq!(lines[3], "3,2022-12-03 05:33:20,,2000,,,,");
+    assert_eq!(lines[25], "25,,,,,,,");
+}
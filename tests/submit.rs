@@ -0,0 +1,144 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use advent_of_code_2022::submit::{self, Hint, Outcome, Submissions};
+use advent_of_code_2022::Challenge;
+
+use common::{flag_client, MockServer, Response, FLAG};
+
+mod common;
+
+const CORRECT: &str = "<main><article><p>That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer to collecting enough star fruit. [<a href=\"/2022/day/1#part2\">Continue to Part Two</a>]</p></article></main>";
+const TOO_HIGH: &str = "<main><article><p>That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data. Please wait one minute before trying again. [<a href=\"/2022/day/1\">Return to Day 1</a>]</p></article></main>";
+const TOO_LOW: &str = "<main><article><p>That's not the right answer; your answer is too low.  Please wait one minute before trying again. [<a href=\"/2022/day/1\">Return to Day 1</a>]</p></article></main>";
+const INCORRECT: &str = "<main><article><p>That's not the right answer.  If you're stuck, make sure you're using the full input data. [<a href=\"/2022/day/1\">Return to Day 1</a>]</p></article></main>";
+const RATE_LIMITED: &str = "<main><article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 52s left to wait. [<a href=\"/2022/day/1\">Return to Day 1</a>]</p></article></main>";
+const ALREADY_SOLVED: &str = "<main><article><p>You don't seem to be solving the right level.  Did you already complete it? [<a href=\"/2022/day/1\">Return to Day 1</a>]</p></article></main>";
+
+async fn submit_with_response(
+    challenge: &str,
+    answer: &str,
+    body: &str,
+) -> anyhow::Result<Outcome> {
+    let server = MockServer::start(vec![Response::ok(body)]);
+    let outcome = submit::submit(&flag_client(), &server.url, &challenge.parse()?, answer).await;
+    server.finish();
+    outcome
+}
+
+fn temporary_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("aoc-{}-{}.toml", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn posts_the_answer_with_the_session() {
+    let server = MockServer::start(vec![Response::ok(CORRECT)]);
+    let challenge = "16b".parse::<Challenge>().unwrap();
+    let outcome = submit::submit(&flag_client(), &server.url, &challenge, "1707")
+        .await
+        .unwrap();
+    let requests = server.finish();
+
+    assert_eq!(outcome, Outcome::Correct);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/2022/day/16/answer");
+    assert_eq!(
+        requests[0].header("cookie"),
+        Some(format!("session={}", FLAG).as_str())
+    );
+    assert_eq!(requests[0].body, "level=2&answer=1707");
+}
+
+#[tokio::test]
+async fn parses_incorrect_answers() {
+    let outcome = submit_with_response("1a", "1", TOO_HIGH).await.unwrap();
+    assert_eq!(outcome, Outcome::Incorrect(Some(Hint::TooHigh)));
+
+    let outcome = submit_with_response("1a", "1", TOO_LOW).await.unwrap();
+    assert_eq!(outcome, Outcome::Incorrect(Some(Hint::TooLow)));
+
+    let outcome = submit_with_response("1a", "1", INCORRECT).await.unwrap();
+    assert_eq!(outcome, Outcome::Incorrect(None));
+}
+
+#[tokio::test]
+async fn parses_the_rate_limit_wait() {
+    let outcome = submit_with_response("1a", "1", RATE_LIMITED).await.unwrap();
+    assert_eq!(outcome, Outcome::RateLimited(Duration::from_secs(292)));
+
+    let body = RATE_LIMITED.replace("4m 52s", "34s");
+    let outcome = submit_with_response("1a", "1", &body).await.unwrap();
+    assert_eq!(outcome, Outcome::RateLimited(Duration::from_secs(34)));
+}
+
+#[tokio::test]
+async fn parses_already_solved_challenges() {
+    let outcome = submit_with_response("1a", "1", ALREADY_SOLVED)
+        .await
+        .unwrap();
+    assert_eq!(outcome, Outcome::AlreadySolved);
+}
+
+#[tokio::test]
+async fn fails_on_unexpected_responses() {
+    let error = submit_with_response("1a", "1", "<article><p>Something else</p></article>")
+        .await
+        .unwrap_err();
+
+    assert!(error.to_string().contains("Something else"), "{}", error);
+}
+
+#[tokio::test]
+async fn fails_on_client_errors() {
+    let server = MockServer::start(vec![Response::new(400, "Bad Request")]);
+    let result = submit::submit(&flag_client(), &server.url, &"1a".parse().unwrap(), "1").await;
+    server.finish();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn never_resends_rejected_answers() {
+    let path = temporary_path("submissions");
+    let challenge = "1a".parse::<Challenge>().unwrap();
+
+    let mut submissions = Submissions::load(path.clone()).unwrap();
+    submissions.check(&challenge, "100").unwrap();
+    submissions.record(&challenge, "100", &Outcome::Incorrect(Some(Hint::TooHigh)));
+    submissions.record(&challenge, "20", &Outcome::Incorrect(Some(Hint::TooLow)));
+    submissions.record(&challenge, "42", &Outcome::Incorrect(None));
+    submissions.record(
+        &challenge,
+        "50",
+        &Outcome::RateLimited(Duration::from_secs(60)),
+    );
+    submissions.save().unwrap();
+
+    let submissions = Submissions::load(path.clone()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(submissions.check(&challenge, "100").is_err());
+    assert!(submissions.check(&challenge, "150").is_err());
+    assert!(submissions.check(&challenge, "20").is_err());
+    assert!(submissions.check(&challenge, "10").is_err());
+    assert!(submissions.check(&challenge, "42").is_err());
+    assert!(submissions.check(&challenge, "50").is_ok());
+    assert!(submissions.check(&"1b".parse().unwrap(), "100").is_ok());
+}
+
+#[test]
+fn never_resubmits_solved_challenges() {
+    let path = temporary_path("solved");
+    let challenge = "1a".parse::<Challenge>().unwrap();
+
+    let mut submissions = Submissions::load(path).unwrap();
+    assert!(!submissions.is_solved(&challenge));
+    submissions.record(&challenge, "24000", &Outcome::Correct);
+    assert!(submissions.is_solved(&challenge));
+
+    assert!(submissions.check(&challenge, "24000").is_err());
+    assert!(submissions.check(&challenge, "24001").is_err());
+}